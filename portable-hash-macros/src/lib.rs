@@ -1,17 +1,24 @@
 #![doc = include_str!("../README.md")]
 
 use proc_macro::TokenStream as TokenStream1;
-use proc_macro2::{Ident, Literal, Span, TokenStream};
-use quote::{format_ident, quote, ToTokens};
+use proc_macro2::{Delimiter, Ident, Literal, Spacing, Span, TokenStream, TokenTree};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
 use syn::{
-    parse_macro_input,
+    ext::IdentExt,
+    meta::ParseNestedMeta,
+    parse_macro_input, parse_quote,
+    punctuated::Punctuated,
     spanned::Spanned,
-    ConstParam, Data, DeriveInput, Error, Expr, Fields, Generics, Index, Lifetime,
-    LifetimeParam, Lit, Token, TypeParam, WhereClause,
+    ConstParam, Data, DeriveInput, Error, Expr, Field, Fields, Generics, Index, Lifetime,
+    LifetimeParam, Lit, LitInt, Path, Token, Type, TypeParam, WhereClause, WherePredicate,
 };
 
-fn crate_root() -> TokenStream {
-    quote!(::portable_hash)
+/// The path to the `portable_hash` crate, overridden by `#[portable_hash(crate = "...")]`.
+fn crate_root(config: &ContainerConfig) -> TokenStream {
+    match &config.crate_path {
+        Some(path) => quote!(#path),
+        None => quote!(::portable_hash),
+    }
 }
 
 /// FNV-1a 64-bit hash for compile-time variant name hashing.
@@ -35,7 +42,7 @@ fn fnv1a_64(bytes: &[u8]) -> u64 {
 }
 
 // ---------------------------------------------------------------------------
-// Enum configuration types
+// Configuration types
 // ---------------------------------------------------------------------------
 
 /// Controls how enum variant discriminants are computed.
@@ -58,17 +65,68 @@ enum DiscriminantWidth {
     U32,
     /// `write_u64` for all variants (default for name/index modes).
     U64,
-    /// `write_isize` for all variants (writes as i64 portably).
+    /// `write_i64` for all variants, the portable encoding of `write_isize`.
     Isize,
     /// Match the enum's `#[repr(...)]` type. Defaults to `isize` if no repr.
     /// Only valid in compat mode.
     Repr,
 }
 
-/// Parsed enum-level configuration.
-struct EnumConfig {
+/// The namespace mixed into name-based discriminants.
+enum Namespace {
+    /// `#[portable_hash(namespace)]`: use the enum's type name.
+    TypeName,
+    /// `#[portable_hash(namespace = "...")]`: use an explicit namespace.
+    Explicit(String),
+}
+
+/// A removed variant listed in `#[portable_hash(retired = [...])]`.
+enum Retired {
+    /// A removed variant name, hashed like a live variant name.
+    Name(String),
+    /// A removed discriminant value, which may be negative in index mode.
+    Discriminant(i128),
+}
+
+/// The kind of item the derive is applied to.
+#[derive(Clone, Copy, PartialEq)]
+enum ContainerKind {
+    Struct,
+    Enum,
+    Union,
+}
+
+/// Container attributes that are only valid on enums.
+const ENUM_ATTRS: &[&str] = &["discriminant", "discriminant_width", "namespace", "retired"];
+
+/// Container attributes that are only valid on unions.
+const UNION_ATTRS: &[&str] = &["union_tag", "variants"];
+
+/// Parsed container-level configuration for structs, enums, and unions.
+struct ContainerConfig {
     mode: DiscriminantMode,
     width: Option<DiscriminantWidth>,
+    /// Namespace for name-based discriminants: `#[portable_hash(namespace = "Status")]`
+    namespace: Option<(Namespace, Span)>,
+    /// Removed variants that must never be reused: `#[portable_hash(retired = ["Old", 7])]`
+    retired: Vec<(Retired, Span)>,
+    /// Replacement for the inferred where-clause: `#[portable_hash(bound = "T: Trait")]`
+    bound: Option<Vec<WherePredicate>>,
+    /// Path to the `portable_hash` crate: `#[portable_hash(crate = "my_sdk::hash")]`
+    crate_path: Option<Path>,
+    /// The union field holding the tag: `#[portable_hash(union_tag = "tag")]`
+    union_tag: Option<Ident>,
+    /// Union fields keyed by tag value: `#[portable_hash(variants(int = 1, float = 2))]`
+    union_variants: Vec<(Ident, LitInt)>,
+    /// The foreign type this definition mirrors: `#[portable_hash(remote = "other::Type")]`
+    remote: Option<Path>,
+    /// Hash as the single non-skipped field: `#[portable_hash(transparent)]`
+    transparent: Option<Span>,
+    /// Treat every field as `skip_if_default`: `#[portable_hash(evolvable)]`
+    evolvable: Option<Span>,
+    /// Derive `TryPortableHash` instead, with an optional error type:
+    /// `#[portable_hash(fallible = "MyError")]`
+    fallible: Option<(Option<Type>, Span)>,
 }
 
 /// Parsed per-variant attributes.
@@ -77,17 +135,99 @@ struct VariantConfig {
     discriminant_override: Option<(u64, Span)>,
     /// Rename for hashing: `#[portable_hash(rename = "OldName")]`
     rename: Option<(String, Span)>,
+    /// Previous names checked for collisions: `#[portable_hash(alias = "OlderName")]`
+    aliases: Vec<(String, Span)>,
+}
+
+/// How a field with `#[portable_hash(encoding = "...")]` is written, in place of its
+/// `PortableHash` impl.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Encoding {
+    /// LEB128 varint of the value widened to `u64`.
+    Varint,
+    /// The value's big-endian bytes.
+    BigEndian,
+    /// The value widened to a fixed-width integer: `"u8"`, `"u16"`, `"u32"`, or `"u64"`.
+    Fixed(&'static str),
+    /// The elements of an array, without a length prefix.
+    FixedLen,
+}
+
+impl Encoding {
+    fn name(self) -> &'static str {
+        match self {
+            Encoding::Varint => "varint",
+            Encoding::BigEndian => "be",
+            Encoding::Fixed(width) => width,
+            Encoding::FixedLen => "fixed_len",
+        }
+    }
+}
+
+/// Parsed per-field attributes.
+struct FieldConfig {
+    /// Replacement for the bounds inferred from this field: `#[portable_hash(bound = "T: Trait")]`
+    bound: Option<Vec<WherePredicate>>,
+    /// Remote mirror used to hash this field: `#[portable_hash(with = "DurationDef")]`
+    with: Option<Path>,
+    /// Leave this field out of the hash: `#[portable_hash(skip)]`
+    skip: bool,
+    /// Only hash this field, with a tag, when it isn't the default value:
+    /// `#[portable_hash(skip_if_default)]`
+    skip_if_default: bool,
+    /// Write this field with an alternative encoding: `#[portable_hash(encoding = "varint")]`
+    encoding: Option<Encoding>,
 }
 
 // ---------------------------------------------------------------------------
 // Attribute parsing
 // ---------------------------------------------------------------------------
 
-/// Parse enum-level `#[portable_hash(...)]` attributes.
-fn parse_enum_attrs(attrs: &[syn::Attribute]) -> Result<EnumConfig, Error> {
-    let mut config = EnumConfig {
+/// Parse a `bound = "..."` attribute value into where-clause predicates.
+///
+/// An empty string is valid and produces no predicates, removing all inferred bounds.
+fn parse_bound(meta: &ParseNestedMeta) -> Result<Vec<WherePredicate>, Error> {
+    let value = meta.value()?;
+    let lit: Lit = value.parse()?;
+    match &lit {
+        Lit::Str(s) => {
+            let predicates = s.parse_with(Punctuated::<WherePredicate, Token![,]>::parse_terminated)?;
+            Ok(predicates.into_iter().collect())
+        }
+        _ => Err(Error::new(
+            lit.span(),
+            "bound must be a string literal, such as `\"T: PortableHash\"`",
+        )),
+    }
+}
+
+/// Parse a bracketed list of literals, such as `aliases = ["Old", "Older"]`.
+fn parse_lit_array(meta: &ParseNestedMeta) -> Result<Vec<Lit>, Error> {
+    let value = meta.value()?;
+    let content;
+    syn::bracketed!(content in value);
+    let lits = Punctuated::<Lit, Token![,]>::parse_terminated(&content)?;
+    Ok(lits.into_iter().collect())
+}
+
+/// Parse container-level `#[portable_hash(...)]` attributes on a struct, enum, or union.
+///
+/// Discriminant attributes are rejected on structs and unions, and union attributes are rejected
+/// on structs and enums.
+fn parse_container_attrs(attrs: &[syn::Attribute], kind: ContainerKind) -> Result<ContainerConfig, Error> {
+    let mut config = ContainerConfig {
         mode: DiscriminantMode::Name,
         width: None,
+        namespace: None,
+        retired: Vec::new(),
+        bound: None,
+        crate_path: None,
+        union_tag: None,
+        union_variants: Vec::new(),
+        remote: None,
+        transparent: None,
+        evolvable: None,
+        fallible: None,
     };
 
     for attr in attrs {
@@ -96,7 +236,142 @@ fn parse_enum_attrs(attrs: &[syn::Attribute]) -> Result<EnumConfig, Error> {
         }
 
         attr.parse_nested_meta(|meta| {
-            if meta.path.is_ident("discriminant") {
+            if meta.path.is_ident("bound") {
+                config.bound = Some(parse_bound(&meta)?);
+            } else if meta.path.is_ident("crate") {
+                let value = meta.value()?;
+                let lit: Lit = value.parse()?;
+                match &lit {
+                    Lit::Str(s) => config.crate_path = Some(s.parse()?),
+                    _ => {
+                        return Err(Error::new(
+                            lit.span(),
+                            "crate must be a string literal, such as `\"my_sdk::hash\"`",
+                        ))
+                    }
+                }
+            } else if meta.path.is_ident("remote") {
+                if kind == ContainerKind::Union {
+                    return Err(Error::new(meta.path.span(), "`remote` isn't supported on unions"));
+                }
+                let value = meta.value()?;
+                let lit: Lit = value.parse()?;
+                match &lit {
+                    Lit::Str(s) => config.remote = Some(s.parse()?),
+                    _ => {
+                        return Err(Error::new(
+                            lit.span(),
+                            "remote must be a string literal, such as `\"other_crate::Type\"`",
+                        ))
+                    }
+                }
+            } else if meta.path.is_ident("transparent") {
+                if kind != ContainerKind::Struct {
+                    return Err(Error::new(
+                        meta.path.span(),
+                        "`transparent` is only supported on structs",
+                    ));
+                }
+                config.transparent = Some(meta.path.span());
+            } else if meta.path.is_ident("evolvable") {
+                if kind != ContainerKind::Struct {
+                    return Err(Error::new(
+                        meta.path.span(),
+                        "`evolvable` is only supported on structs",
+                    ));
+                }
+                config.evolvable = Some(meta.path.span());
+            } else if meta.path.is_ident("fallible") {
+                let span = meta.path.span();
+                if kind == ContainerKind::Union {
+                    return Err(Error::new(span, "`fallible` isn't supported on unions"));
+                }
+                if !meta.input.peek(Token![=]) {
+                    config.fallible = Some((None, span));
+                    return Ok(());
+                }
+                let value = meta.value()?;
+                let lit: Lit = value.parse()?;
+                match &lit {
+                    Lit::Str(s) => config.fallible = Some((Some(s.parse()?), span)),
+                    _ => {
+                        return Err(Error::new(
+                            lit.span(),
+                            "fallible must be a string literal naming the error type, such as `\"MyError\"`",
+                        ))
+                    }
+                }
+            } else if kind != ContainerKind::Enum && ENUM_ATTRS.iter().any(|a| meta.path.is_ident(a)) {
+                return Err(Error::new(
+                    meta.path.span(),
+                    "discriminant attributes are only supported on enums",
+                ));
+            } else if kind != ContainerKind::Union && UNION_ATTRS.iter().any(|a| meta.path.is_ident(a)) {
+                return Err(Error::new(
+                    meta.path.span(),
+                    "`union_tag` and `variants` are only supported on unions",
+                ));
+            } else if meta.path.is_ident("union_tag") {
+                let value = meta.value()?;
+                let lit: Lit = value.parse()?;
+                match &lit {
+                    Lit::Str(s) => config.union_tag = Some(s.parse()?),
+                    _ => {
+                        return Err(Error::new(
+                            lit.span(),
+                            "union_tag must be a string literal naming the tag field",
+                        ))
+                    }
+                }
+            } else if meta.path.is_ident("variants") {
+                meta.parse_nested_meta(|variant| {
+                    let field = variant.path.require_ident()?.clone();
+                    let value: LitInt = variant.value()?.parse()?;
+                    config.union_variants.push((field, value));
+                    Ok(())
+                })?;
+            } else if meta.path.is_ident("retired") {
+                let value = meta.value()?;
+                let content;
+                syn::bracketed!(content in value);
+                for expr in Punctuated::<Expr, Token![,]>::parse_terminated(&content)? {
+                    match &expr {
+                        Expr::Lit(syn::ExprLit { lit: Lit::Str(s), .. }) => {
+                            config.retired.push((Retired::Name(s.value()), s.span()));
+                        }
+                        Expr::Lit(syn::ExprLit { lit: Lit::Int(_), .. }) | Expr::Unary(_) => {
+                            // Parsed like explicit discriminants, so negative values can be retired.
+                            let val = parse_discriminant_expr(&expr)?;
+                            config.retired.push((Retired::Discriminant(val), expr.span()));
+                        }
+                        _ => {
+                            return Err(Error::new(
+                                expr.span(),
+                                "retired entries must be variant name strings or integer discriminants",
+                            ))
+                        }
+                    }
+                }
+            } else if meta.path.is_ident("namespace") {
+                let span = meta.path.span();
+                if !meta.input.peek(Token![=]) {
+                    config.namespace = Some((Namespace::TypeName, span));
+                    return Ok(());
+                }
+                let value = meta.value()?;
+                let lit: Lit = value.parse()?;
+                match &lit {
+                    Lit::Str(s) if !s.value().is_empty() => {
+                        config.namespace = Some((Namespace::Explicit(s.value()), s.span()));
+                    }
+                    _ => {
+                        return Err(Error::new(
+                            lit.span(),
+                            "namespace must be a non-empty string literal",
+                        ))
+                    }
+                }
+            } else if meta.path.is_ident("discriminant") {
                 let value = meta.value()?;
                 let lit: Lit = value.parse()?;
                 match &lit {
@@ -152,22 +427,77 @@ fn parse_enum_attrs(attrs: &[syn::Attribute]) -> Result<EnumConfig, Error> {
             } else {
                 return Err(Error::new(
                     meta.path.span(),
-                    "unknown portable_hash enum attribute, expected \
-                     `discriminant` or `discriminant_width`",
+                    match kind {
+                        ContainerKind::Struct => "unknown portable_hash struct attribute, expected `bound`, \
+                                                  `crate`, `evolvable`, `fallible`, `remote`, or `transparent`",
+                        ContainerKind::Enum => "unknown portable_hash enum attribute, expected `bound`, `crate`, \
+                                                `discriminant`, `discriminant_width`, `fallible`, `namespace`, \
+                                                `remote`, or `retired`",
+                        ContainerKind::Union => "unknown portable_hash union attribute, expected `bound`, `crate`, \
+                                                 `union_tag`, or `variants`",
+                    },
                 ));
             }
             Ok(())
         })?;
     }
 
+    if let (Some(span), Some(_)) = (config.transparent, &config.remote) {
+        return Err(Error::new(
+            span,
+            "`transparent` can't be combined with `remote`, as the remote type's layout can't be checked",
+        ));
+    }
+
+    if let (Some(span), Some(_)) = (config.evolvable, config.transparent) {
+        return Err(Error::new(
+            span,
+            "`evolvable` can't be combined with `transparent`, which hashes the field exactly as its own type",
+        ));
+    }
+
+    if let (Some((_, span)), Some(_)) = (&config.fallible, config.transparent) {
+        return Err(Error::new(
+            *span,
+            "`fallible` can't be combined with `transparent`, which hashes the field exactly as its own type",
+        ));
+    }
+
+    if let (Some((_, span)), Some(_)) = (&config.fallible, &config.remote) {
+        return Err(Error::new(
+            *span,
+            "`fallible` can't be combined with `remote`, as `PortableHashRemote` impls can't return errors",
+        ));
+    }
+
+    if let (Some((_, span)), DiscriminantMode::Index) = (&config.namespace, config.mode) {
+        return Err(Error::new(
+            *span,
+            "`namespace` is only valid with name-based discriminants (the default)",
+        ));
+    }
+
+    if config.mode == DiscriminantMode::Index {
+        for (retired, span) in &config.retired {
+            if let Retired::Name(_) = retired {
+                return Err(Error::new(
+                    *span,
+                    "retired variant names are only valid with name-based discriminants (the \
+                     default), retire the discriminant value instead",
+                ));
+            }
+        }
+    }
+
     Ok(config)
 }
 
 /// Parse variant-level `#[portable_hash(...)]` attributes.
-fn parse_variant_attrs(attrs: &[syn::Attribute], config: &EnumConfig) -> Result<VariantConfig, Error> {
+fn parse_variant_attrs(attrs: &[syn::Attribute], config: &ContainerConfig) -> Result<VariantConfig, Error> {
     let mut var_config = VariantConfig {
         discriminant_override: None,
         rename: None,
+        aliases: Vec::new(),
     };
 
     for attr in attrs {
@@ -207,10 +537,37 @@ fn parse_variant_attrs(attrs: &[syn::Attribute], config: &EnumConfig) -> Result<
                         ))
                     }
                 }
+            } else if meta.path.is_ident("alias") {
+                let value = meta.value()?;
+                let lit: Lit = value.parse()?;
+                match &lit {
+                    Lit::Str(s) => {
+                        var_config.aliases.push((s.value(), s.span()));
+                    }
+                    _ => {
+                        return Err(Error::new(
+                            lit.span(),
+                            "alias must be a string literal",
+                        ))
+                    }
+                }
+            } else if meta.path.is_ident("aliases") {
+                for lit in parse_lit_array(&meta)? {
+                    match &lit {
+                        Lit::Str(s) => var_config.aliases.push((s.value(), s.span())),
+                        _ => {
+                            return Err(Error::new(
+                                lit.span(),
+                                "aliases must be a list of string literals",
+                            ))
+                        }
+                    }
+                }
             } else {
                 return Err(Error::new(
                     meta.path.span(),
-                    "unknown portable_hash variant attribute, expected `discriminant` or `rename`",
+                    "unknown portable_hash variant attribute, expected `alias`, `aliases`, \
+                     `discriminant`, or `rename`",
                 ));
             }
             Ok(())
@@ -225,8 +582,7 @@ fn parse_variant_attrs(attrs: &[syn::Attribute], config: &EnumConfig) -> Result<
         ));
     }
 
-    if var_config.rename.is_some() && config.mode == DiscriminantMode::Index {
-        let (_, span) = var_config.rename.as_ref().unwrap();
+    if let (Some((_, span)), DiscriminantMode::Index) = (&var_config.rename, config.mode) {
         return Err(Error::new(
             *span,
             "`rename` is only valid with name-based discriminants (the default), \
@@ -234,13 +590,149 @@ fn parse_variant_attrs(attrs: &[syn::Attribute], config: &EnumConfig) -> Result<
         ));
     }
 
+    if let (Some((_, span)), DiscriminantMode::Index) = (var_config.aliases.first(), config.mode) {
+        return Err(Error::new(
+            *span,
+            "`alias` and `aliases` are only valid with name-based discriminants (the default)",
+        ));
+    }
+
     Ok(var_config)
 }
 
+/// Parse field-level `#[portable_hash(...)]` attributes.
+fn parse_field_attrs(attrs: &[syn::Attribute]) -> Result<FieldConfig, Error> {
+    let mut field_config = FieldConfig {
+        bound: None,
+        with: None,
+        skip: false,
+        skip_if_default: false,
+        encoding: None,
+    };
+
+    for attr in attrs {
+        if !attr.path().is_ident("portable_hash") {
+            continue;
+        }
+
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("bound") {
+                field_config.bound = Some(parse_bound(&meta)?);
+            } else if meta.path.is_ident("skip") {
+                field_config.skip = true;
+            } else if meta.path.is_ident("skip_if_default") {
+                field_config.skip_if_default = true;
+            } else if meta.path.is_ident("encoding") {
+                let value = meta.value()?;
+                let lit: Lit = value.parse()?;
+                match &lit {
+                    Lit::Str(s) => {
+                        field_config.encoding = Some(match s.value().as_str() {
+                            "varint" => Encoding::Varint,
+                            "be" => Encoding::BigEndian,
+                            "u8" => Encoding::Fixed("u8"),
+                            "u16" => Encoding::Fixed("u16"),
+                            "u32" => Encoding::Fixed("u32"),
+                            "u64" => Encoding::Fixed("u64"),
+                            "fixed_len" => Encoding::FixedLen,
+                            other => {
+                                return Err(Error::new(
+                                    s.span(),
+                                    format!(
+                                        "unknown encoding `{}`, expected `\"varint\"`, `\"be\"`, `\"u8\"`, \
+                                         `\"u16\"`, `\"u32\"`, `\"u64\"`, or `\"fixed_len\"`", other
+                                    ),
+                                ))
+                            }
+                        });
+                    }
+                    _ => {
+                        return Err(Error::new(
+                            lit.span(),
+                            "encoding must be a string literal",
+                        ))
+                    }
+                }
+            } else if meta.path.is_ident("with") {
+                let value = meta.value()?;
+                let lit: Lit = value.parse()?;
+                match &lit {
+                    Lit::Str(s) => field_config.with = Some(s.parse()?),
+                    _ => {
+                        return Err(Error::new(
+                            lit.span(),
+                            "with must be a string literal naming a remote mirror type",
+                        ))
+                    }
+                }
+            } else {
+                return Err(Error::new(
+                    meta.path.span(),
+                    "unknown portable_hash field attribute, expected `bound`, `encoding`, `skip`, \
+                     `skip_if_default`, or `with`",
+                ));
+            }
+            Ok(())
+        })?;
+
+        if field_config.skip
+            && (field_config.bound.is_some()
+                || field_config.with.is_some()
+                || field_config.encoding.is_some()
+                || field_config.skip_if_default)
+        {
+            return Err(Error::new(
+                attr.span(),
+                "skipped fields aren't hashed, so `bound`, `encoding`, `skip_if_default`, and `with` \
+                 can't be used with `skip`",
+            ));
+        }
+        if field_config.with.is_some() && field_config.encoding.is_some() {
+            return Err(Error::new(
+                attr.span(),
+                "`encoding` and `with` can't be used together",
+            ));
+        }
+    }
+
+    Ok(field_config)
+}
+
 // ---------------------------------------------------------------------------
 // Discriminant value helpers
 // ---------------------------------------------------------------------------
 
+/// Compute the name-based discriminant of a variant.
+///
+/// Without a namespace this is `fnv1a_64(name)`. With a namespace, the namespace and name are
+/// joined as `"{namespace}::{name}"` before hashing, so identically named variants of different
+/// enums get different discriminants.
+fn name_discriminant(namespace: Option<&str>, name: &str) -> u64 {
+    match namespace {
+        Some(namespace) => fnv1a_64(format!("{}::{}", namespace, name).as_bytes()),
+        None => fnv1a_64(name.as_bytes()),
+    }
+}
+
+/// Fit a name-based discriminant to the enum's discriminant width.
+///
+/// Narrower widths xor-fold the 64-bit FNV-1a hash in half until it fits: `u32` is the high half
+/// xor the low half, `u16` folds the `u32` value again, and `u8` folds the `u16` value. Folding
+/// keeps the bits that FNV-1a mixes best, unlike masking off the low bits. `isize` reinterprets
+/// the 64 bits as an `i64`.
+fn fit_name_discriminant(hash: u64, width: DiscriminantWidth) -> i128 {
+    let fold32 = (hash >> 32) ^ (hash & 0xffff_ffff);
+    let fold16 = (fold32 >> 16) ^ (fold32 & 0xffff);
+    let fold8 = (fold16 >> 8) ^ (fold16 & 0xff);
+    match width {
+        DiscriminantWidth::U8 => fold8 as i128,
+        DiscriminantWidth::U16 => fold16 as i128,
+        DiscriminantWidth::U32 => fold32 as i128,
+        DiscriminantWidth::U64 => hash as i128,
+        DiscriminantWidth::Isize | DiscriminantWidth::Repr => hash as i64 as i128,
+    }
+}
+
 /// Parse a Rust discriminant expression (`= 42`, `= -1`) into an i128.
 fn parse_discriminant_expr(expr: &Expr) -> Result<i128, Error> {
     match expr {
@@ -289,7 +781,7 @@ fn resolve_repr_width(attrs: &[syn::Attribute]) -> DiscriminantWidth {
 }
 
 /// Determine the effective width for the enum.
-fn effective_width(config: &EnumConfig, all_attrs: &[syn::Attribute]) -> DiscriminantWidth {
+fn effective_width(config: &ContainerConfig, all_attrs: &[syn::Attribute]) -> DiscriminantWidth {
     if let Some(w) = config.width {
         if w == DiscriminantWidth::Repr {
             resolve_repr_width(all_attrs)
@@ -341,57 +833,764 @@ fn disc_write_tokens(width: DiscriminantWidth, value: i128, span: Span) -> Resul
             Ok((Ident::new("write_u64", span), quote!(#lit)))
         }
         DiscriminantWidth::Isize | DiscriminantWidth::Repr => {
-            // write_isize; our PortableHasher converts to i64 portably.
+            // `write_isize` writes an `i64`, so write one directly: casting the value to `isize`
+            // would truncate 64-bit name hashes on 32-bit targets.
             if value < i64::MIN as i128 || value > i64::MAX as i128 {
                 return Err(Error::new(span, format!(
                     "discriminant value {} does not fit in i64/isize", value
                 )));
             }
-            let v = value as i64;
-            Ok((Ident::new("write_isize", span), quote!(#v as isize)))
+            let lit = Literal::i64_suffixed(value as i64);
+            Ok((Ident::new("write_i64", span), quote!(#lit)))
         }
     }
 }
 
 // ---------------------------------------------------------------------------
-// Derive macro
+// Trait bound inference
 // ---------------------------------------------------------------------------
 
-/// Derives [`PortableHash`] for structs and enums.
-///
-/// # Structs
-///
-/// Fields are hashed in declaration order. Reordering or removing fields changes the hash
-/// output. Renaming fields is safe.
-///
-/// # Enums
-///
-/// By default, each variant is identified by a **name-based discriminant**: the variant's
-/// name is hashed at compile time via FNV-1a 64-bit, and the resulting `u64` is written to
-/// the hasher. This means:
-///
-/// - **Reordering** variants is safe (hash output is unchanged).
-/// - **Renaming** a variant is a breaking change (hash output changes).
-///
-/// ## Enum Attributes
+/// Collects the where-clause predicates for a derived impl from the container's fields.
 ///
-/// ### `#[portable_hash(discriminant = "...")]`
+/// Only type parameters are bounded, and only those that appear in a field outside of a
+/// `PhantomData`, so recursive types and private field types never end up in the public bounds.
+/// A field whose type reaches through a type parameter (`T::Item`, `Vec<T::Item>`) is bounded by
+/// its whole type instead, as the associated type may be hashable when `T` itself is not.
 ///
-/// Controls how variant discriminants are computed:
+/// Fields only hashed when they differ from their default are compared with `Default` and
+/// `PartialEq`, which can't be inferred from the type parameters, so generic field types are
+/// bounded by their whole type for those traits. Likewise, the fields of a `fallible` container
+/// are bounded by their whole type, as their errors must convert into the container's error.
+struct BoundCollector<'a> {
+    type_params: Vec<&'a Ident>,
+    used: Vec<bool>,
+    field_types: Vec<&'a Type>,
+    default_types: Vec<&'a Type>,
+    fallible_types: Vec<&'a Type>,
+    predicates: Vec<WherePredicate>,
+    evolvable: bool,
+    fallible: bool,
+}
+
+impl<'a> BoundCollector<'a> {
+    fn new(generics: &'a Generics, evolvable: bool, fallible: bool) -> Self {
+        let type_params: Vec<_> = generics.type_params().map(|t| &t.ident).collect();
+        let used = vec![false; type_params.len()];
+        Self {
+            type_params,
+            used,
+            field_types: Vec::new(),
+            default_types: Vec::new(),
+            fallible_types: Vec::new(),
+            predicates: Vec::new(),
+            evolvable,
+            fallible,
+        }
+    }
+
+    fn add_field(&mut self, field: &'a Field) -> Result<(), Error> {
+        let field_config = parse_field_attrs(&field.attrs)?;
+        if field_config.skip {
+            // Skipped fields aren't hashed, so they need no bounds.
+            return Ok(());
+        }
+        if let Some(bound) = field_config.bound {
+            self.predicates.extend(bound);
+            return Ok(());
+        }
+
+        if (field_config.skip_if_default || self.evolvable)
+            && !is_phantom_data(&field.ty)
+            && self.mentions_type_param(field.ty.to_token_stream())
+        {
+            self.default_types.push(&field.ty);
+        }
+        if field_config.with.is_some() {
+            // The remote mirror's impl carries its own bounds.
+        } else if field_config.encoding.map_or(false, |e| e != Encoding::FixedLen) {
+            // Integer encodings convert the field rather than hashing it.
+        } else if self.fallible && field_config.encoding.is_none() {
+            if !is_phantom_data(&field.ty) && self.mentions_type_param(field.ty.to_token_stream()) {
+                self.fallible_types.push(&field.ty);
+            }
+        } else if !is_phantom_data(&field.ty) && self.scan(field.ty.to_token_stream()) {
+            self.field_types.push(&field.ty);
+        }
+        Ok(())
+    }
+
+    /// Whether any type parameter is named in `tokens`.
+    fn mentions_type_param(&self, tokens: TokenStream) -> bool {
+        tokens.into_iter().any(|tt| match tt {
+            TokenTree::Group(group) => self.mentions_type_param(group.stream()),
+            TokenTree::Ident(ident) => self.type_params.iter().any(|p| **p == ident),
+            _ => false,
+        })
+    }
+
+    /// Marks each type parameter named in `tokens`, returning true if any type parameter is
+    /// followed by `::`, or by `as` in a qualified path such as `<T as Trait>::Item`. Type
+    /// parameters that are only used through an associated type are not marked, as the field type
+    /// is bounded instead.
+    fn scan(&mut self, tokens: TokenStream) -> bool {
+        let mut associated = false;
+        let mut iter = tokens.into_iter().peekable();
+        while let Some(tt) = iter.next() {
+            match tt {
+                TokenTree::Group(group) => associated |= self.scan(group.stream()),
+                TokenTree::Ident(ident) => {
+                    if let Some(i) = self.type_params.iter().position(|p| **p == ident) {
+                        match iter.peek() {
+                            Some(TokenTree::Punct(p)) if p.as_char() == ':' && p.spacing() == Spacing::Joint => {
+                                associated = true;
+                            }
+                            Some(TokenTree::Ident(next)) if next == "as" => associated = true,
+                            _ => self.used[i] = true,
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        associated
+    }
+}
+
+/// Whether a field type is `PhantomData<...>`, which is hashable for any type parameter.
+fn is_phantom_data(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) => path.qself.is_none()
+            && path.path.segments.last().map_or(false, |s| s.ident == "PhantomData"),
+        _ => false,
+    }
+}
+
+/// Compute the where-clause predicates for the derived impl.
 ///
-/// - `"name"` (default) — hash the variant name at compile time.
-/// - `"index"` — use the variant's Rust discriminant value (0, 1, 2, ... or explicit `= N`).
-///   Reordering breaks hashes but renaming is safe.
+/// A container-level `bound` replaces the inferred bounds entirely, while a field-level `bound`
+/// replaces the bounds inferred from that field. Field-level bounds are always included.
+fn infer_bounds(
+    data: &Data,
+    generics: &Generics,
+    config: &ContainerConfig,
+    hash: &TokenStream,
+    try_hash: &TokenStream,
+    error: &TokenStream,
+) -> Result<Vec<WherePredicate>, Error> {
+    let mut collector = BoundCollector::new(generics, config.evolvable.is_some(), config.fallible.is_some());
+    match data {
+        Data::Struct(x) => {
+            for field in x.fields.iter() {
+                collector.add_field(field)?;
+            }
+        }
+        Data::Enum(x) => {
+            for field in x.variants.iter().flat_map(|v| v.fields.iter()) {
+                collector.add_field(field)?;
+            }
+        }
+        Data::Union(x) => {
+            for field in x.fields.named.iter() {
+                collector.add_field(field)?;
+            }
+        }
+    }
+
+    let mut predicates = collector.predicates;
+    match &config.bound {
+        Some(bound) => predicates.extend(bound.iter().cloned()),
+        None => {
+            for (param, used) in collector.type_params.iter().zip(collector.used) {
+                if used {
+                    predicates.push(parse_quote!(#param: #hash));
+                }
+            }
+            for ty in collector.field_types {
+                predicates.push(parse_quote!(#ty: #hash));
+            }
+            for ty in collector.default_types {
+                predicates.push(parse_quote!(#ty: ::core::default::Default + ::core::cmp::PartialEq));
+            }
+            for ty in collector.fallible_types {
+                predicates.push(parse_quote!(#ty: #try_hash));
+                predicates.push(parse_quote!(#error: ::core::convert::From<<#ty as #try_hash>::Error>));
+            }
+        }
+    }
+    Ok(predicates)
+}
+
+// ---------------------------------------------------------------------------
+// Field hashing
+// ---------------------------------------------------------------------------
+
+/// Generate the statements that hash one field, given an expression borrowing the field and the
+/// field's name (or position) for tracing.
+///
+/// Fields marked `#[portable_hash(with = "Mirror")]` are hashed by the mirror's
+/// `PortableHashRemote` impl instead of their own `PortableHash` impl. Fields marked
+/// `#[portable_hash(skip)]` produce no statements. Fields marked
+/// `#[portable_hash(skip_if_default)]`, or every field of an `evolvable` struct, are only hashed
+/// when they differ from their default, after a tag hashed from the field's name.
+///
+/// Fields of a `fallible` container are hashed by their `TryPortableHash` impl, returning any
+/// error. `fallible` holds the `trace_exit` calls for the paths the field is nested in, such as
+/// its enum variant, which run before an error is returned so that trace calls stay balanced.
+fn hash_field(
+    field: &Field,
+    access: TokenStream,
+    label: &str,
+    evolvable: bool,
+    fallible: Option<&TokenStream>,
+    root: &TokenStream,
+) -> Result<TokenStream, Error> {
+    let field_config = parse_field_attrs(&field.attrs)?;
+    if field_config.skip {
+        return Ok(TokenStream::new());
+    }
+    // Encodings convert with `From`, so a field type that doesn't fit the encoding's integer type
+    // fails to compile, with the error pointing at the field type.
+    let ty = &field.ty;
+    let span = ty.span();
+    // Fields hashed by their `TryPortableHash` impl produce a `Result` rather than statements.
+    let try_hash = fallible.is_some() && field_config.with.is_none() && field_config.encoding.is_none();
+    let hash = match (field_config.with, field_config.encoding) {
+        (Some(with), _) => quote! {
+            <#with as #root::PortableHashRemote<_>>::portable_hash_remote(#access, state);
+        },
+        (None, Some(Encoding::Varint)) => quote_spanned! {span=>
+            {
+                #[allow(clippy::useless_conversion)]
+                let encoded = <::core::primitive::u64 as ::core::convert::From<#ty>>::from(*#access);
+                #root::encoding::write_varint(state, encoded);
+            }
+        },
+        (None, Some(Encoding::BigEndian)) => quote_spanned! {span=>
+            <#ty as #root::encoding::BigEndian>::write_be(#access, state);
+        },
+        (None, Some(Encoding::Fixed(width))) => {
+            let method = format_ident!("write_{}", width);
+            let width = Ident::new(width, span);
+            quote_spanned! {span=>
+                {
+                    #[allow(clippy::useless_conversion)]
+                    let encoded = <::core::primitive::#width as ::core::convert::From<#ty>>::from(*#access);
+                    state.#method(encoded);
+                }
+            }
+        }
+        (None, Some(Encoding::FixedLen)) => quote_spanned! {span=>
+            #root::encoding::hash_fixed_len(#access, state);
+        },
+        // The result is returned after `trace_exit`, which is emitted below, so tracing hashers
+        // see balanced calls when a field fails.
+        (None, None) if try_hash => quote! {
+            #root::TryPortableHash::try_portable_hash(#access, state)
+        },
+        (None, None) => quote! {
+            #root::PortableHash::portable_hash(#access, state);
+        },
+    };
+    // The tag keeps a non-default field from being confused with a neighbouring field when the
+    // fields between them are left out.
+    let hash = if field_config.skip_if_default || evolvable {
+        let tag = field_tag(label);
+        let otherwise = if try_hash {
+            quote!(else { ::core::result::Result::Ok(()) })
+        } else {
+            TokenStream::new()
+        };
+        quote_spanned! {span=>
+            if !<#ty as ::core::cmp::PartialEq>::eq(#access, &<#ty as ::core::default::Default>::default()) {
+                state.write_u64(#tag);
+                #hash
+            } #otherwise
+        }
+    } else {
+        hash
+    };
+    // Fallible containers stop at the first error a hasher records, such as an exceeded limit,
+    // rather than hashing the remaining fields.
+    let bail = fallible.map(|exits| quote! {
+        #exits
+        return ::core::result::Result::Err(::core::convert::From::from(error));
+    });
+    let check = match &bail {
+        Some(bail) => quote! {
+            if let ::core::result::Result::Err(error) = #root::PortableHasher::check(state) {
+                #bail
+            }
+        },
+        None => TokenStream::new(),
+    };
+    Ok(if try_hash {
+        quote! {
+            state.trace_enter(#root::PathSegment::Field(#label));
+            {
+                let result = #hash;
+                state.trace_exit();
+                if let ::core::result::Result::Err(error) = result {
+                    #bail
+                }
+            }
+            #check
+        }
+    } else {
+        quote! {
+            state.trace_enter(#root::PathSegment::Field(#label));
+            #hash
+            state.trace_exit();
+            #check
+        }
+    })
+}
+
+/// The tag written before a `skip_if_default` field that differs from its default: the FNV-1a
+/// hash of its name (or position), like a name-based enum discriminant.
+fn field_tag(label: &str) -> u64 {
+    fnv1a_64(label.as_bytes())
+}
+
+/// The tag written after the fields of an `evolvable` struct: the tag of an empty name, which no
+/// field can have.
+fn end_tag() -> u64 {
+    field_tag("")
+}
+
+/// Write the end tag after the fields of an `evolvable` struct.
+///
+/// An `evolvable` struct whose fields are all default writes nothing else, so without it
+/// `[changed, default]` and `[default, changed]` would hash identically. Structs and variants
+/// that only have some `skip_if_default` fields don't write it, so that adding their first one
+/// keeps existing hashes.
+fn end_tag_tokens(evolvable: bool) -> TokenStream {
+    if evolvable {
+        let tag = end_tag();
+        quote!(state.write_u64(#tag);)
+    } else {
+        TokenStream::new()
+    }
+}
+
+/// The name reported for a field in trace paths: the identifier without any `r#` prefix, or the
+/// field's position for tuple fields.
+fn field_label(field: &Field, index: usize) -> String {
+    match &field.ident {
+        Some(ident) => ident.unraw().to_string(),
+        None => index.to_string(),
+    }
+}
+
+/// Assert that a remote mirror's field has the type of the foreign field it mirrors, so that the
+/// mirror's encodings and schema describe the value that is actually hashed. Skipped fields
+/// aren't bound in enum patterns, and aren't hashed, so they aren't checked.
+fn remote_field_check(field: &Field, access: &TokenStream) -> Result<TokenStream, Error> {
+    if parse_field_attrs(&field.attrs)?.skip {
+        return Ok(TokenStream::new());
+    }
+    let ty = &field.ty;
+    Ok(quote_spanned! {ty.span()=>
+        let _: &#ty = #access;
+    })
+}
+
+/// Strip generic arguments from the last segment of a remote type path, so that it can be used
+/// in enum variant patterns (`other::Either::Left` rather than `other::Either<L, R>::Left`).
+fn pattern_path(path: &Path) -> Path {
+    let mut path = path.clone();
+    if let Some(last) = path.segments.last_mut() {
+        last.arguments = syn::PathArguments::None;
+    }
+    path
+}
+
+// ---------------------------------------------------------------------------
+// Schema descriptors
+// ---------------------------------------------------------------------------
+
+/// Render tokens as a compact string, independent of `proc_macro2`'s display formatting.
+///
+/// Tokens are concatenated with a space only between adjacent identifiers or literals, and after
+/// commas and semicolons, so `Vec < (u8, & 'a str) >` renders as `Vec<(u8, &'a str)>`.
+fn compact_tokens(tokens: TokenStream) -> String {
+    fn write(tokens: TokenStream, out: &mut String) {
+        let mut prev_word = false;
+        for tt in tokens {
+            match tt {
+                TokenTree::Group(group) => {
+                    let (open, close) = match group.delimiter() {
+                        Delimiter::Parenthesis => ("(", ")"),
+                        Delimiter::Brace => ("{", "}"),
+                        Delimiter::Bracket => ("[", "]"),
+                        Delimiter::None => ("", ""),
+                    };
+                    out.push_str(open);
+                    write(group.stream(), out);
+                    out.push_str(close);
+                    prev_word = false;
+                }
+                TokenTree::Ident(ident) => {
+                    if prev_word {
+                        out.push(' ');
+                    }
+                    out.push_str(&ident.to_string());
+                    prev_word = true;
+                }
+                TokenTree::Literal(lit) => {
+                    if prev_word {
+                        out.push(' ');
+                    }
+                    out.push_str(&lit.to_string());
+                    prev_word = true;
+                }
+                TokenTree::Punct(punct) => {
+                    out.push(punct.as_char());
+                    if punct.as_char() == ',' || punct.as_char() == ';' {
+                        out.push(' ');
+                    }
+                    prev_word = false;
+                }
+            }
+        }
+    }
+
+    let mut out = String::new();
+    write(tokens, &mut out);
+    out
+}
+
+/// Describe how a single field is hashed: its type as written, and any field attributes that
+/// change how it's hashed.
+///
+/// Field names are only included for `skip_if_default` fields, as their tag is hashed from it.
+fn field_schema(field: &Field, label: &str, evolvable: bool) -> Result<String, Error> {
+    let field_config = parse_field_attrs(&field.attrs)?;
+    let ty = compact_tokens(field.ty.to_token_stream());
+    let schema = match (field_config.with, field_config.encoding) {
+        (Some(with), _) => format!("with({}) {}", compact_tokens(with.to_token_stream()), ty),
+        (None, Some(encoding)) => format!("encoding({}) {}", encoding.name(), ty),
+        (None, None) => ty,
+    };
+    Ok(if field_config.skip_if_default || evolvable {
+        format!("skip_if_default({}) {}", label, schema)
+    } else {
+        schema
+    })
+}
+
+/// Describe the hashed fields of a struct or variant in order, as `(A, B, C)`. Skipped fields are
+/// left out.
+fn fields_schema<'a>(fields: impl IntoIterator<Item = &'a Field>, evolvable: bool) -> Result<String, Error> {
+    let mut schemas = Vec::new();
+    for (i, field) in fields.into_iter().enumerate() {
+        if !parse_field_attrs(&field.attrs)?.skip {
+            schemas.push(field_schema(field, &field_label(field, i), evolvable)?);
+        }
+    }
+    Ok(format!("({})", schemas.join(", ")))
+}
+
+/// Whether the container has a `#[repr(transparent)]` attribute.
+fn is_repr_transparent(attrs: &[syn::Attribute]) -> bool {
+    attrs.iter().any(|attr| {
+        attr.path().is_ident("repr")
+            && attr
+                .meta
+                .require_list()
+                .map(|list| list.tokens.to_string() == "transparent")
+                .unwrap_or(false)
+    })
+}
+
+/// The name of a discriminant width, as written in `discriminant_width`.
+fn width_name(width: DiscriminantWidth) -> &'static str {
+    match width {
+        DiscriminantWidth::U8 => "u8",
+        DiscriminantWidth::U16 => "u16",
+        DiscriminantWidth::U32 => "u32",
+        DiscriminantWidth::U64 => "u64",
+        DiscriminantWidth::Isize => "isize",
+        // `effective_width` resolves `repr` to the enum's `#[repr(...)]` width before the schema
+        // is written.
+        DiscriminantWidth::Repr => unreachable!("`repr` width is resolved by `effective_width`"),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Derive macro
+// ---------------------------------------------------------------------------
+
+/// Derives [`PortableHash`] for structs, enums, and tagged unions.
+///
+/// # Structs
+///
+/// Fields are hashed in declaration order. Reordering or removing fields changes the hash
+/// output. Renaming fields is safe.
+///
+/// ### `#[portable_hash(skip)]`
+///
+/// On a struct or enum variant field, leaves the field out of the hash entirely, as if it were
+/// removed. Skipped fields don't need to implement `PortableHash` and add no trait bounds.
+///
+/// ### `#[portable_hash(skip_if_default)]`
+///
+/// On a struct or enum variant field, only hashes the field when it isn't equal to its
+/// `Default` value, writing a `u64` tag hashed from the field's name before the value.
+///
+/// Adding a `skip_if_default` field leaves the hashes of existing values unchanged as long as it
+/// holds its default, so persisted hashes survive the new field. Renaming the field changes the
+/// hash of values where it isn't default. The field type must implement `Default` and
+/// `PartialEq`.
+///
+/// Nothing marks the end of the fields, so a value whose `skip_if_default` fields are default is
+/// only told apart from its neighbours in a sequence by its other fields. A struct that could
+/// have no other fields written should be `evolvable` instead, which adds an end tag.
+///
+/// ```ignore
+/// #[derive(PortableHash)]
+/// struct Order {
+///     id: u64,
+///     #[portable_hash(skip_if_default)]
+///     discount: u32,
+///     // Added later: orders without a note keep their previous hash.
+///     #[portable_hash(skip_if_default)]
+///     note: String,
+/// }
+/// ```
+///
+/// ### `#[portable_hash(evolvable)]`
+///
+/// On a struct, treats every field as `skip_if_default`, and writes a constant `u64` end tag after
+/// the fields, so that a value whose fields are all default can't run into the next value in a
+/// sequence. Fields can then be added anywhere without changing the hashes of values whose added
+/// fields are default, and removing a field is safe for values where it was default. Reordering
+/// fields still changes the hash, and so does making an existing struct `evolvable`. Every field
+/// type must implement `Default` and `PartialEq`.
+///
+/// ### `#[portable_hash(encoding = "...")]`
+///
+/// On a struct or enum variant field, writes the field with an alternative encoding instead of
+/// its `PortableHash` impl, to match externally specified hash formats:
+///
+/// - `"varint"` — an unsigned LEB128 varint. The field type must convert into `u64`.
+/// - `"be"` — the value's big-endian bytes. The field type must be a fixed-width integer.
+/// - `"u8"`, `"u16"`, `"u32"`, `"u64"` — the matching `write_*` method. The field type must
+///   convert into the target type without loss, so `bool` and `u8` fields can use `"u64"`, but
+///   `u32` fields can't use `"u8"`.
+///
+/// - `"fixed_len"` — the elements of an array field, without the length prefix that arrays
+///   normally share with slices. The length is fixed by the type, including for const generic
+///   lengths such as `[u8; N]`, so the prefix is redundant.
+///
+/// Fields that don't fit the encoding are a compile error.
+///
+/// ```ignore
+/// #[derive(PortableHash)]
+/// struct Header {
+///     #[portable_hash(encoding = "varint")]
+///     len: u32,
+///     #[portable_hash(encoding = "be")]
+///     kind: u16,
+/// }
+/// ```
+///
+/// ### `#[portable_hash(transparent)]`
+///
+/// On a `#[repr(transparent)]` struct, hashes the value exactly as its one non-skipped field,
+/// which is checked at compile time. Slices of the struct are hashed as slices of the field, so
+/// `Vec<UserId>` hashes the same as `Vec<u64>`, and keeps fast paths such as `u8`'s
+/// `write_bytes` override. The hashed field must be the struct's only field with a non-zero size,
+/// which is checked by a constant, so a struct that keeps its data in a skipped field fails to
+/// compile.
+///
+/// The generated slice impl uses `unsafe` to reinterpret the slice, under
+/// `#[allow(unsafe_code)]`. That's enough for crates that `deny(unsafe_code)`, but crates that
+/// `forbid(unsafe_code)` can't derive `transparent`: leave it off, and hash the struct like any
+/// other, which hashes slices element by element.
+///
+/// ```ignore
+/// #[derive(PortableHash)]
+/// #[repr(transparent)]
+/// #[portable_hash(transparent)]
+/// struct UserId(u64);
+/// ```
+///
+/// # Schema
+///
+/// The derive also implements `PortableSchema`, whose `PORTABLE_HASH_SCHEMA` constant describes
+/// the hashing layout: field types in order, and for enums the discriminant mode, width, and
+/// each variant's discriminant and fields. Field names are included for `skip_if_default` fields.
+/// Snapshot it with `portable-hash-tester` to catch changes that alter hashes.
+///
+/// # Trait Bounds
+///
+/// The generated impl bounds each type parameter that appears in a field with `PortableHash`,
+/// ignoring parameters that only appear in `PhantomData`. Field types themselves are not bounded,
+/// so recursive types such as `struct Node { children: Vec<Node> }` derive without overflow, and
+/// private field types don't leak into the public impl. Fields that use an associated type of a
+/// type parameter (`T::Item` or `<T as Trait>::Item`) are bounded by their full field type, as are
+/// generic `skip_if_default` fields, which are bounded by `Default + PartialEq`.
+///
+/// The inference only sees type parameters, not the bounds that field types' `PortableHash` impls
+/// require of them. A `BTreeMap<K, V>` field bounds `K: PortableHash` and `V: PortableHash`, but
+/// not the `K: PortableOrd` that the map's impl also requires, so the derive fails to compile with
+/// an error at the derive for a missing `PortableOrd`. Add it with
+/// `#[portable_hash(bound = "K: PortableHash + PortableOrd, V: PortableHash")]` on the field or
+/// container.
+///
+/// ### `#[portable_hash(bound = "T: PortableHash + Trait")]`
+///
+/// On a struct or enum, replaces the inferred bounds with the given where-clause predicates. On
+/// a field, replaces the bounds inferred from that field only. `bound = ""` removes the bounds.
+///
+/// ```ignore
+/// #[derive(PortableHash)]
+/// #[portable_hash(bound = "T: PortableHash + Ord")]
+/// struct Sorted<T: Ord> {
+///     items: Vec<T>,
+/// }
+///
+/// #[derive(PortableHash)]
+/// struct Cached<T, U> {
+///     inner: T,
+///     #[portable_hash(bound = "U: PortableHash + Clone")]
+///     cached: U,
+/// }
+/// ```
+///
+/// # Fallible Hashing
+///
+/// ### `#[portable_hash(fallible)]`
+///
+/// On a struct or enum, derives `TryPortableHash` instead of `PortableHash`. Each field is hashed
+/// by its `TryPortableHash` impl, and the first error is returned, so the type can contain fields
+/// that only hash after passing validation. Every `PortableHash` type implements
+/// `TryPortableHash`, so other fields need no changes. The error type is `HashError`, or the type
+/// named by `#[portable_hash(fallible = "MyError")]`, which must implement `From` for the error
+/// type of every field, including `From<HashError>`. The hash of a value that hashes successfully
+/// is the same as without `fallible`.
+///
+/// After each field, the generated impl also returns any error recorded by the hasher's
+/// `PortableHasher::check`, so hashing through a `LimitedHasher` stops soon after a limit is
+/// exceeded.
+///
+/// Generic field types are bounded by their whole type with `TryPortableHash`, and the error type
+/// with `From<<Field as TryPortableHash>::Error>`. Containers such as `Vec<Finite>` don't
+/// implement `TryPortableHash` for fallible elements, so fields holding them need a newtype
+/// with a hand-written impl that calls `encoding::try_hash_slice`.
+///
+/// ```ignore
+/// #[derive(PortableHash)]
+/// #[portable_hash(fallible = "ReadingError")]
+/// struct Reading {
+///     sensor: u32,
+///     value: Finite, // implements `TryPortableHash<Error = ReadingError>`
+/// }
+/// ```
+///
+/// # Re-exporting Crates
+///
+/// ### `#[portable_hash(crate = "path::to::portable_hash")]`
+///
+/// The generated impl refers to `::portable_hash` by default. Crates that re-export
+/// `portable_hash` can point the derive at the re-export instead, so downstream users don't need
+/// a direct dependency on `portable-hash`:
+///
+/// ```ignore
+/// use my_sdk::hash::PortableHash;
+///
+/// #[derive(PortableHash)]
+/// #[portable_hash(crate = "my_sdk::hash")]
+/// struct Event {
+///     id: u64,
+/// }
+/// ```
+///
+/// # Remote Types
+///
+/// ### `#[portable_hash(remote = "other_crate::Type")]`
+///
+/// Types from other crates can't implement `PortableHash` due to the orphan rule. Instead, derive
+/// on a local mirror definition with the same fields (or variants) as the foreign type, which
+/// must all be accessible. The derive implements `PortableHashRemote<other_crate::Type>` for the
+/// mirror rather than `PortableHash`, hashing the foreign value exactly as the mirror would be
+/// hashed. Each mirror field's type is checked against the foreign field's type at compile time.
+/// Mirror fields are never read, so the mirror may need `#[allow(dead_code)]`. Unions can't be
+/// mirrored, as the foreign union's tag contract can't be checked.
+///
+/// ### `#[portable_hash(with = "Mirror")]`
+///
+/// On a field of a foreign type, hashes the field with the mirror's `PortableHashRemote` impl.
+/// Foreign values can also be hashed directly through the `portable_hash::Remote` wrapper.
+///
+/// ```ignore
+/// #[derive(PortableHash)]
+/// #[portable_hash(remote = "other_crate::Point")]
+/// #[allow(dead_code)]
+/// struct PointDef {
+///     x: i32,
+///     y: i32,
+/// }
+///
+/// #[derive(PortableHash)]
+/// struct Line {
+///     #[portable_hash(with = "PointDef")]
+///     start: other_crate::Point,
+///     #[portable_hash(with = "PointDef")]
+///     end: other_crate::Point,
+/// }
+/// ```
+///
+/// # Enums
+///
+/// By default, each variant is identified by a **name-based discriminant**: the variant's
+/// name is hashed at compile time via FNV-1a 64-bit, and the resulting `u64` is written to
+/// the hasher. This means:
+///
+/// - **Reordering** variants is safe (hash output is unchanged).
+/// - **Renaming** a variant is a breaking change (hash output changes).
+///
+/// ## Enum Attributes
+///
+/// ### `#[portable_hash(discriminant = "...")]`
+///
+/// Controls how variant discriminants are computed:
+///
+/// - `"name"` (default) — hash the variant name at compile time.
+/// - `"index"` — use the variant's Rust discriminant value (0, 1, 2, ... or explicit `= N`).
+///   Reordering breaks hashes but renaming is safe.
 ///
 /// ### `#[portable_hash(discriminant_width = "...")]`
 ///
 /// Controls the write method used for all discriminants in the enum:
 ///
 /// - `"u64"` (default) — `write_u64` for all variants.
-/// - `"u8"`, `"u16"`, `"u32"` — fixed width; compile error if any discriminant doesn't fit.
-/// - `"isize"` — `write_isize` (converts to i64 portably).
+/// - `"u8"`, `"u16"`, `"u32"` — fixed width; compile error if any index or manual discriminant
+///   doesn't fit.
+/// - `"isize"` — `write_i64`, which is how `write_isize` hashes an `isize` on every platform.
 /// - `"repr"` — match the enum's `#[repr(...)]` type (defaults to `isize` if no repr).
 ///
+/// Name-based discriminants are folded to fit narrower widths: `u32` is the 64-bit FNV-1a hash's
+/// high half xor its low half, `u16` folds the `u32` value the same way, and `u8` folds the
+/// `u16` value. `isize` writes the same 64 bits as an `i64`, on 32-bit targets too. Collision
+/// checks run on the folded values, so a narrower width may reject variant names that don't
+/// collide as `u64`.
+///
+/// ### `#[portable_hash(namespace = "...")]`
+///
+/// Mixes a namespace into name-based discriminants, hashing `"{namespace}::{name}"` instead of
+/// the bare variant name. Without a namespace, `Status::Active` and `Plan::Active` have the same
+/// discriminant, so identical payloads hash identically. A bare `#[portable_hash(namespace)]`
+/// uses the enum's type name, in which case renaming the enum is a breaking change. Only valid
+/// in name mode.
+///
+/// ### `#[portable_hash(retired = ["RemovedVariant", 42])]`
+///
+/// Lists removed variants that must never be reused, as either variant names (name mode only) or
+/// raw discriminant values, which may be negative in index mode. Adding a variant whose
+/// discriminant matches a retired entry is a compile error, protecting stored hashes of the
+/// removed variant from being reinterpreted.
+///
 /// ## Variant Attributes
 ///
 /// ### `#[portable_hash(discriminant = <integer>)]`
@@ -402,6 +1601,55 @@ fn disc_write_tokens(width: DiscriminantWidth, value: i128, span: Span) -> Resul
 ///
 /// Hashes "OldName" instead of the variant's actual name. Only valid in name mode.
 ///
+/// ### `#[portable_hash(alias = "PreviousName")]`
+///
+/// Records a previous name of the variant without changing its hash. The alias's discriminant is
+/// included in the compile-time collision check, so no other variant can reuse a discriminant
+/// that previously identified this variant in stored hashes. May be repeated. Only valid in name
+/// mode.
+///
+/// ### `#[portable_hash(aliases = ["Older", "Oldest"])]`
+///
+/// Equivalent to repeating `alias` for each name, for variants with a long rename history.
+///
+/// # Unions
+///
+/// Unions can only be hashed when one of their fields identifies the active field, such as a
+/// `#[repr(C)]` FFI union whose members all begin with a common integer tag:
+///
+/// ```ignore
+/// #[derive(PortableHash)]
+/// #[repr(C)]
+/// #[portable_hash(union_tag = "tag", variants(key = 1, mouse = 2))]
+/// union Event {
+///     tag: u8,
+///     key: KeyEvent,     // #[repr(C)] struct KeyEvent { tag: u8, code: u32 }
+///     mouse: MouseEvent, // #[repr(C)] struct MouseEvent { tag: u8, x: i16, y: i16 }
+/// }
+///
+/// // SAFETY: see the safety contract below.
+/// unsafe impl PortableHashUnion for Event {}
+/// ```
+///
+/// The generated impl hashes the tag field, then matches its value against the integer literals
+/// in `variants(...)` and hashes the matching field. A tag value not listed in `variants(...)`
+/// hashes only the tag. Union fields not listed are never hashed.
+///
+/// ## Safety Contract
+///
+/// Reading a union field is `unsafe`, so the derive requires the union to implement the unsafe
+/// `PortableHashUnion` marker trait, written by hand. The `unsafe impl` asserts the following,
+/// which the generated impl relies on:
+///
+/// - The tag field is initialized in every value of the union. For a tag shared by all members,
+///   the union must be `#[repr(C)]` and every member must begin with the tag at offset zero.
+/// - Whenever the tag holds a value listed in `variants(...)`, the field listed for that value is
+///   initialized with a valid value of its type.
+///
+/// Violating the contract is undefined behaviour. The generated field reads allow `unsafe_code`,
+/// so crates that `#![deny(unsafe_code)]` only need to allow it on the `unsafe impl`. Crates that
+/// `#![forbid(unsafe_code)]` can't derive `PortableHash` for unions.
+///
 /// ## Explicit Rust Discriminant Values
 ///
 /// In index and compat modes, explicit Rust discriminant values are respected:
@@ -416,73 +1664,187 @@ fn disc_write_tokens(width: DiscriminantWidth, value: i128, span: Span) -> Resul
 /// }
 /// ```
 #[proc_macro_derive(PortableHash, attributes(portable_hash))]
-#[allow(non_snake_case)]
 pub fn derive_portable_hash(input: TokenStream1) -> TokenStream1 {
-    let root = crate_root();
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input).into()
+}
+
+/// Generate the `PortableHash` (or `PortableHashRemote`) and `PortableSchema` impls for a derive
+/// input, or the compile errors explaining why it can't be derived.
+#[allow(non_snake_case)]
+fn expand(mut input: DeriveInput) -> TokenStream {
+    let ident = input.ident;
+
+    let kind = match input.data {
+        Data::Struct(_) => ContainerKind::Struct,
+        Data::Enum(_) => ContainerKind::Enum,
+        Data::Union(_) => ContainerKind::Union,
+    };
+    let config = match parse_container_attrs(&input.attrs, kind) {
+        Ok(c) => c,
+        Err(e) => return e.to_compile_error(),
+    };
+
+    let root = crate_root(&config);
     let hash = quote!(#root::PortableHash);
     let hasher_write = quote!(#root::PortableHasher);
+    let try_hash = quote!(#root::TryPortableHash);
+    let error = match &config.fallible {
+        Some((Some(ty), _)) => ty.to_token_stream(),
+        _ => quote!(#root::HashError),
+    };
 
-    let mut input = parse_macro_input!(input as DeriveInput);
-    let ident = input.ident;
+    let predicates = match infer_bounds(&input.data, &input.generics, &config, &hash, &try_hash, &error) {
+        Ok(p) => p,
+        Err(e) => return e.to_compile_error(),
+    };
+
+    // Remote mirrors hash a borrowed foreign value instead of `self`.
+    let (receiver, self_path) = match &config.remote {
+        Some(remote) => {
+            let path = pattern_path(remote);
+            (quote!(value), quote!(#path))
+        }
+        None => (quote!(self), quote!(Self)),
+    };
 
     let mut tokens = TokenStream::new();
-    let mut types = Vec::new();
+    // The statements that reinterpret `data` as `inner`, a slice of the hashed field's type, and
+    // that type, for the overriding slice methods of transparent structs.
+    let mut slice_tokens: Option<(TokenStream, TokenStream)> = None;
+    // The compile-time layout assertion for transparent structs, checked by `slice_tokens`.
+    let mut layout_check: Option<TokenStream> = None;
+
+    // Describes the hashing layout for `PortableSchema`. Only details that affect the hash output
+    // are included, so field and variant names are omitted.
+    let schema: String;
 
     match input.data {
-        // Stability: structs are hashed in the order of their fields.
-        Data::Struct(x) => {
-            // Reject any #[portable_hash] attributes on structs (they're only for enums).
-            for attr in &input.attrs {
-                if attr.path().is_ident("portable_hash") {
-                    return Error::new(
-                        attr.path().span(),
-                        "`#[portable_hash(...)]` attributes are only supported on enums",
-                    )
-                    .to_compile_error()
-                    .into();
-                }
+        // Stability: transparent structs are hashed exactly like their one hashed field, including
+        // in slices.
+        Data::Struct(x) if config.transparent.is_some() => {
+            let span = config.transparent.unwrap();
+            if !is_repr_transparent(&input.attrs) {
+                return Error::new(
+                    span,
+                    "`transparent` requires `#[repr(transparent)]`, so that slices can be hashed as \
+                     slices of the inner field",
+                )
+                .to_compile_error();
             }
 
-            match x.fields {
-                Fields::Named(x) => {
-                    let fields = x.named.iter().map(|x| {
-                        types.push(x.ty.clone());
-                        x.ident.as_ref().unwrap()
-                    });
-                    quote! {
-                        #( #hash::portable_hash(&self.#fields, state); )*
+            let mut hashed = Vec::new();
+            for (i, field) in x.fields.iter().enumerate() {
+                match parse_field_attrs(&field.attrs) {
+                    Ok(field_config) if field_config.skip => {}
+                    Ok(field_config)
+                        if field_config.with.is_some()
+                            || field_config.encoding.is_some()
+                            || field_config.skip_if_default =>
+                    {
+                        return Error::new(
+                            field.span(),
+                            "`with`, `encoding`, and `skip_if_default` can't be used in a `transparent` \
+                             struct, as the field must be hashed by its own `PortableHash` impl",
+                        )
+                        .to_compile_error();
                     }
-                        .to_tokens(&mut tokens)
+                    Ok(_) => hashed.push((i, field)),
+                    Err(e) => return e.to_compile_error(),
                 }
+            }
+            if hashed.len() != 1 {
+                return Error::new(
+                    span,
+                    format!("`transparent` requires exactly one non-skipped field, found {}", hashed.len()),
+                )
+                .to_compile_error();
+            }
 
-                Fields::Unnamed(x) => {
-                    let fields = x.unnamed.iter().enumerate().map(|(i, x)| {
-                        types.push(x.ty.clone());
-                        Index::from(i)
-                    });
-                    quote! {
-                        #( #hash::portable_hash(&self.#fields, state); )*
-                    }
-                        .to_tokens(&mut tokens)
+            let (i, field) = hashed[0];
+            let inner = &field.ty;
+            let access = match &field.ident {
+                Some(name) => quote!(&self.#name),
+                None => {
+                    let index = Index::from(i);
+                    quote!(&self.#index)
                 }
+            };
+            quote!(#hash::portable_hash(#access, state);)
+                .to_tokens(&mut tokens);
+            // `#[repr(transparent)]` only guarantees a matching layout if the other fields are
+            // zero-sized, which leaves the hashed field as the one with data. This is a constant,
+            // so a mismatch fails to compile rather than panicking while hashing.
+            layout_check = Some(quote! {
+                assert!(
+                    ::core::mem::size_of::<Self>() == ::core::mem::size_of::<#inner>()
+                        && ::core::mem::align_of::<Self>() == ::core::mem::align_of::<#inner>(),
+                    "`transparent` field must be the only field with a non-zero size",
+                )
+            });
+            let cast = quote! {
+                let () = Self::__PORTABLE_HASH_TRANSPARENT_LAYOUT;
+                // SAFETY: `Self` is `#[repr(transparent)]` and the layout check above shows that
+                // the hashed field is its non-zero-sized field, so `Self` and the field have the
+                // same layout and `data` is a valid slice of the field type.
+                #[allow(unsafe_code)]
+                let inner = unsafe {
+                    ::core::slice::from_raw_parts(data.as_ptr() as *const #inner, data.len())
+                };
+            };
+            slice_tokens = Some((cast, inner.to_token_stream()));
+            schema = format!("transparent {}", compact_tokens(inner.to_token_stream()));
+        }
+
+        // Stability: structs are hashed in the order of their fields.
+        Data::Struct(x) => {
+            schema = match fields_schema(x.fields.iter(), config.evolvable.is_some()) {
+                // The end tag sets `evolvable` structs apart from ones whose fields are all
+                // `skip_if_default`.
+                Ok(fields) if config.evolvable.is_some() => format!("evolvable struct {}", fields),
+                Ok(fields) => format!("struct {}", fields),
+                Err(e) => return e.to_compile_error(),
+            };
 
-                Fields::Unit => (),
+            for (i, field) in x.fields.iter().enumerate() {
+                let access = match &field.ident {
+                    Some(name) => quote!(&#receiver.#name),
+                    None => {
+                        let index = Index::from(i);
+                        quote!(&#receiver.#index)
+                    }
+                };
+                if config.remote.is_some() {
+                    match remote_field_check(field, &access) {
+                        Ok(t) => t.to_tokens(&mut tokens),
+                        Err(e) => return e.to_compile_error(),
+                    }
+                }
+                let exits = TokenStream::new();
+                let fallible = config.fallible.as_ref().map(|_| &exits);
+                match hash_field(field, access, &field_label(field, i), config.evolvable.is_some(), fallible, &root) {
+                    Ok(t) => t.to_tokens(&mut tokens),
+                    Err(e) => return e.to_compile_error(),
+                }
             }
+            end_tag_tokens(config.evolvable.is_some()).to_tokens(&mut tokens);
         }
 
         Data::Enum(x) => {
-            // Phase 1: Parse enum-level configuration.
-            let config = match parse_enum_attrs(&input.attrs) {
-                Ok(c) => c,
-                Err(e) => return e.to_compile_error().into(),
-            };
-
+            // Phase 1: Resolve the enum-level configuration.
             let width = effective_width(&config, &input.attrs);
 
+            let type_name = ident.to_string();
+            let namespace = config.namespace.as_ref().map(|(ns, _)| match ns {
+                Namespace::TypeName => type_name.as_str(),
+                Namespace::Explicit(ns) => ns.as_str(),
+            });
+
             // Phase 2: Compute discriminant values for all variants.
             struct VariantInfo<'a> {
                 variant: &'a syn::Variant,
                 discriminant: i128,
+                aliases: Vec<(String, i128)>,
             }
 
             let mut variant_infos = Vec::new();
@@ -491,7 +1853,7 @@ pub fn derive_portable_hash(input: TokenStream1) -> TokenStream1 {
             for variant in x.variants.iter() {
                 let var_config = match parse_variant_attrs(&variant.attrs, &config) {
                     Ok(c) => c,
-                    Err(e) => return e.to_compile_error().into(),
+                    Err(e) => return e.to_compile_error(),
                 };
 
                 let discriminant: i128 = match config.mode {
@@ -500,9 +1862,10 @@ pub fn derive_portable_hash(input: TokenStream1) -> TokenStream1 {
                             val as i128
                         } else {
                             let name = var_config.rename
-                                .map(|(s, _)| s)
+                                .as_ref()
+                                .map(|(s, _)| s.clone())
                                 .unwrap_or_else(|| variant.ident.to_string());
-                            fnv1a_64(name.as_bytes()) as i128
+                            fit_name_discriminant(name_discriminant(namespace, &name), width)
                         }
                     }
                     DiscriminantMode::Index => {
@@ -515,7 +1878,7 @@ pub fn derive_portable_hash(input: TokenStream1) -> TokenStream1 {
                             // Explicit Rust discriminant: `Variant = 42`
                             let v = match parse_discriminant_expr(expr) {
                                 Ok(v) => v,
-                                Err(e) => return e.to_compile_error().into(),
+                                Err(e) => return e.to_compile_error(),
                             };
                             next_rust_discriminant = v + 1;
                             v
@@ -528,87 +1891,142 @@ pub fn derive_portable_hash(input: TokenStream1) -> TokenStream1 {
                     }
                 };
 
+                let aliases = var_config.aliases
+                    .into_iter()
+                    .map(|(name, _)| {
+                        let value = fit_name_discriminant(name_discriminant(namespace, &name), width);
+                        (name, value)
+                    })
+                    .collect();
+
                 variant_infos.push(VariantInfo {
                     variant,
                     discriminant,
+                    aliases,
                 });
             }
 
-            // Phase 3: Check uniqueness of discriminant values.
+            // Phase 3: Check uniqueness of discriminant values, including the discriminants of
+            // previous variant names listed via `alias` and removed variants listed via `retired`.
+            // Name-based values are checked after folding to the discriminant width, as that's
+            // what gets hashed.
             {
-                let mut sorted: Vec<(i128, &Ident)> = variant_infos
-                    .iter()
-                    .map(|vi| (vi.discriminant, &vi.variant.ident))
-                    .collect();
+                let mut sorted: Vec<(i128, String)> = Vec::new();
+                for vi in &variant_infos {
+                    let var = &vi.variant.ident;
+                    sorted.push((vi.discriminant, format!("variant `{}`", var)));
+                    for (name, value) in &vi.aliases {
+                        sorted.push((*value, format!("alias `{}` of variant `{}`", name, var)));
+                    }
+                }
+                for (retired, span) in &config.retired {
+                    if let Retired::Discriminant(value) = retired {
+                        // Retired values must fit the width, like the discriminants they retire.
+                        if let Err(e) = disc_write_tokens(width, *value, *span) {
+                            return e.to_compile_error();
+                        }
+                    }
+                    sorted.push(match retired {
+                        Retired::Name(name) => (
+                            fit_name_discriminant(name_discriminant(namespace, name), width),
+                            format!("retired variant `{}`", name),
+                        ),
+                        Retired::Discriminant(value) => (
+                            *value,
+                            format!("retired discriminant {}", value),
+                        ),
+                    });
+                }
                 sorted.sort_by_key(|(val, _)| *val);
                 for window in sorted.windows(2) {
                     if window[0].0 == window[1].0 {
                         return Error::new(
                             Span::call_site(),
                             format!(
-                                "portable_hash discriminant collision: variants `{}` and `{}` \
+                                "portable_hash discriminant collision: {} and {} \
                                  both have discriminant value {}",
                                 window[0].1, window[1].1, window[0].0
                             ),
                         )
-                        .to_compile_error()
-                        .into();
+                        .to_compile_error();
                     }
                 }
             }
 
             // Phase 4: Generate match arms.
             let mut variant_tokens = TokenStream::new();
+            let mut variant_schemas = Vec::new();
 
             for vi in &variant_infos {
                 let var = &vi.variant.ident;
+                let var_label = var.unraw().to_string();
+
+                match fields_schema(vi.variant.fields.iter(), false) {
+                    Ok(fields) => variant_schemas.push((vi.discriminant, fields)),
+                    Err(e) => return e.to_compile_error(),
+                }
 
                 let (disc_method, disc_lit) = match disc_write_tokens(width, vi.discriminant, var.span()) {
                     Ok(t) => t,
-                    Err(e) => return e.to_compile_error().into(),
+                    Err(e) => return e.to_compile_error(),
+                };
+
+                let fields: Vec<_> = match &vi.variant.fields {
+                    Fields::Named(x) => x.named.iter().map(|x| x.ident.clone().unwrap()).collect(),
+                    Fields::Unnamed(x) => (0..x.unnamed.len()).map(|i| format_ident!("_{}", i)).collect(),
+                    Fields::Unit => Vec::new(),
                 };
+                // Skipped named fields are matched with `_` to avoid unused variable warnings.
+                let mut patterns = Vec::new();
+                for (field, name) in vi.variant.fields.iter().zip(&fields) {
+                    match parse_field_attrs(&field.attrs) {
+                        Ok(c) if c.skip && field.ident.is_some() => patterns.push(quote!(#name: _)),
+                        Ok(_) => patterns.push(quote!(#name)),
+                        Err(e) => return e.to_compile_error(),
+                    }
+                }
+                let mut field_tokens = TokenStream::new();
+                for (i, (field, name)) in vi.variant.fields.iter().zip(&fields).enumerate() {
+                    if config.remote.is_some() {
+                        match remote_field_check(field, &quote!(#name)) {
+                            Ok(t) => t.to_tokens(&mut field_tokens),
+                            Err(e) => return e.to_compile_error(),
+                        }
+                    }
+                    let exits = quote!(state.trace_exit(););
+                    let fallible = config.fallible.as_ref().map(|_| &exits);
+                    match hash_field(field, quote!(#name), &field_label(field, i), false, fallible, &root) {
+                        Ok(t) => t.to_tokens(&mut field_tokens),
+                        Err(e) => return e.to_compile_error(),
+                    }
+                }
 
                 match &vi.variant.fields {
-                    Fields::Named(x) => {
-                        let fields: Vec<_> = x
-                            .named
-                            .iter()
-                            .map(|x| {
-                                types.push(x.ty.clone());
-                                x.ident.as_ref().unwrap()
-                            })
-                            .collect();
-                        quote! {
-                            Self::#var { #(#fields),* } => {
-                                state.#disc_method(#disc_lit);
-                                #( #hash::portable_hash(#fields, state); )*
-                            }
+                    Fields::Named(_) => quote! {
+                        #self_path::#var { #(#patterns),* } => {
+                            state.trace_enter(#root::PathSegment::Variant(#var_label));
+                            state.#disc_method(#disc_lit);
+                            #field_tokens
+                            state.trace_exit();
                         }
-                            .to_tokens(&mut variant_tokens);
-                    }
-
-                    Fields::Unnamed(x) => {
-                        let fields: Vec<_> = x
-                            .unnamed
-                            .iter()
-                            .enumerate()
-                            .map(|(i, x)| {
-                                types.push(x.ty.clone());
-                                format_ident!("_{}", i)
-                            })
-                            .collect();
-                        quote! {
-                            Self::#var(#(#fields),*) => {
-                                state.#disc_method(#disc_lit);
-                                #( #hash::portable_hash(#fields, state); )*
-                            }
+                    }
+                        .to_tokens(&mut variant_tokens),
+
+                    Fields::Unnamed(_) => quote! {
+                        #self_path::#var(#(#patterns),*) => {
+                            state.trace_enter(#root::PathSegment::Variant(#var_label));
+                            state.#disc_method(#disc_lit);
+                            #field_tokens
+                            state.trace_exit();
                         }
-                            .to_tokens(&mut variant_tokens);
                     }
+                        .to_tokens(&mut variant_tokens),
 
                     Fields::Unit => quote! {
-                        Self::#var => {
+                        #self_path::#var => {
+                            state.trace_enter(#root::PathSegment::Variant(#var_label));
                             state.#disc_method(#disc_lit);
+                            state.trace_exit();
                         },
                     }
                         .to_tokens(&mut variant_tokens),
@@ -616,23 +2034,177 @@ pub fn derive_portable_hash(input: TokenStream1) -> TokenStream1 {
             }
 
             quote! {
-                match self {
+                match #receiver {
                     #variant_tokens
                 }
             }
                 .to_tokens(&mut tokens);
+
+            // Variants are listed by discriminant, as declaration order doesn't affect hashing
+            // in name mode.
+            variant_schemas.sort_by_key(|(discriminant, _)| *discriminant);
+            let variant_schemas: Vec<_> = variant_schemas
+                .iter()
+                .map(|(discriminant, fields)| format!("{}: {}", discriminant, fields))
+                .collect();
+            let mode = match config.mode {
+                DiscriminantMode::Name => "name",
+                DiscriminantMode::Index => "index",
+            };
+            schema = format!(
+                "enum discriminant={} width={} {{ {} }}",
+                mode,
+                width_name(width),
+                variant_schemas.join(", "),
+            );
         }
 
-        Data::Union(_) => {
-            return Error::new(ident.span(), "can't derive `PortableHash` for union")
-                .to_compile_error()
-                .into()
+        // Safety: unions are hashed by trusting the tag, as asserted by the user's
+        // `PortableHashUnion` impl.
+        Data::Union(x) => {
+            let tag = match &config.union_tag {
+                Some(tag) => tag,
+                None => {
+                    return Error::new(
+                        ident.span(),
+                        "deriving `PortableHash` for a union requires \
+                         `#[portable_hash(union_tag = \"field\", variants(...))]`",
+                    )
+                    .to_compile_error()
+                }
+            };
+
+            let has_field = |name: &Ident| x.fields.named.iter().any(|f| f.ident.as_ref() == Some(name));
+            if !has_field(tag) {
+                return Error::new(tag.span(), format!("union has no field named `{}`", tag))
+                    .to_compile_error();
+            }
+            if config.union_variants.is_empty() {
+                return Error::new(
+                    ident.span(),
+                    "`variants(...)` must list at least one union field and its tag value",
+                )
+                .to_compile_error();
+            }
+
+            let mut seen: Vec<(i128, &Ident)> = Vec::new();
+            for (field, value) in &config.union_variants {
+                if !has_field(field) || field == tag {
+                    return Error::new(
+                        field.span(),
+                        format!("`{}` is not a non-tag field of this union", field),
+                    )
+                    .to_compile_error();
+                }
+                let value_i128 = match value.base10_parse::<i128>() {
+                    Ok(v) => v,
+                    Err(e) => return e.to_compile_error(),
+                };
+                if let Some((_, other)) = seen.iter().find(|(v, _)| *v == value_i128) {
+                    return Error::new(
+                        value.span(),
+                        format!("union fields `{}` and `{}` both have tag value {}", other, field, value),
+                    )
+                    .to_compile_error();
+                }
+                seen.push((value_i128, field));
+            }
+
+            for field in &x.fields.named {
+                match parse_field_attrs(&field.attrs) {
+                    Ok(c) if c.skip => {
+                        return Error::new(
+                            field.span(),
+                            "`skip` isn't supported on union fields, fields left out of `variants(...)` \
+                             are never hashed",
+                        )
+                        .to_compile_error();
+                    }
+                    Ok(c) if c.skip_if_default => {
+                        return Error::new(
+                            field.span(),
+                            "`skip_if_default` isn't supported on union fields",
+                        )
+                        .to_compile_error();
+                    }
+                    Ok(_) => {}
+                    Err(e) => return e.to_compile_error(),
+                }
+            }
+
+            let tag_field = x.fields.named.iter().find(|f| f.ident.as_ref() == Some(tag)).unwrap();
+            let mut variant_schemas = Vec::new();
+            let mut arms = TokenStream::new();
+            for (name, value) in &config.union_variants {
+                let field = x.fields.named.iter().find(|f| f.ident.as_ref() == Some(name)).unwrap();
+                match field_schema(field, &field_label(field, 0), false) {
+                    Ok(field) => variant_schemas.push(format!("{}: {}", value, field)),
+                    Err(e) => return e.to_compile_error(),
+                }
+                let hash_stmt = match hash_field(field, quote!(value), &field_label(field, 0), false, None, &root) {
+                    Ok(t) => t,
+                    Err(e) => return e.to_compile_error(),
+                };
+                quote! {
+                    #value => {
+                        // SAFETY: the `PortableHashUnion` impl asserts that the field listed for
+                        // this tag value in `variants(...)` is initialized.
+                        #[allow(unsafe_code)]
+                        let value = unsafe { &#receiver.#name };
+                        #hash_stmt
+                    }
+                }
+                    .to_tokens(&mut arms);
+            }
+            let tag_label = tag.unraw().to_string();
+            quote! {
+                // Only unions with a hand-written `unsafe impl PortableHashUnion` can be derived.
+                fn assert_union_contract<T: ?Sized + #root::PortableHashUnion>() {}
+                assert_union_contract::<Self>();
+
+                // SAFETY: the `PortableHashUnion` impl asserts that the tag field is initialized
+                // for every value of this union.
+                #[allow(unsafe_code)]
+                let tag = unsafe { &#receiver.#tag };
+                state.trace_enter(#root::PathSegment::Field(#tag_label));
+                #hash::portable_hash(tag, state);
+                state.trace_exit();
+                // Tag values not listed in `variants(...)` hash only the tag.
+                match *tag {
+                    #arms
+                    _ => {}
+                }
+            }
+                .to_tokens(&mut tokens);
+
+            schema = format!(
+                "union tag={} {{ {} }}",
+                compact_tokens(tag_field.ty.to_token_stream()),
+                variant_schemas.join(", "),
+            );
         }
     }
 
-    input.generics.make_where_clause();
-    let wc = input.generics.where_clause.as_mut().unwrap();
-    let where_ = fix_where(Some(wc));
+    // The schema doesn't depend on any trait bounds, so it uses the type's own where-clause.
+    let schema_impl = {
+        let SplitGenerics {
+            lti,
+            ltt,
+            tpi,
+            tpt,
+            cpi,
+            cpt,
+            wc,
+        } = split_generics(&input.generics);
+        quote! {
+            impl<#(#lti,)* #(#tpi,)* #(#cpi,)*> #root::PortableSchema for #ident<#(#ltt,)* #(#tpt,)* #(#cpt),*> #wc
+            {
+                const PORTABLE_HASH_SCHEMA: &'static str = #schema;
+            }
+        }
+    };
+
+    input.generics.make_where_clause().predicates.extend(predicates);
     let SplitGenerics {
         lti,
         ltt,
@@ -642,38 +2214,99 @@ pub fn derive_portable_hash(input: TokenStream1) -> TokenStream1 {
         cpt,
         wc,
     } = split_generics(&input.generics);
-    quote! {
-        impl<#(#lti,)* #(#tpi,)* #(#cpi,)*> #hash for #ident<#(#ltt,)* #(#tpt,)* #(#cpt),*> #where_ #wc
-            #( #types: #hash ),*
+    let slice_fn = slice_tokens.map(|(cast, inner)| quote! {
+        #[inline]
+        fn portable_hash_slice<H: #hasher_write>(data: &[Self], state: &mut H)
+        where
+            Self: Sized,
         {
-            #[inline]
-            fn portable_hash<H: #hasher_write>(&self, state: &mut H) {
-                #tokens
-            }
+            #cast
+            <#inner as #hash>::portable_hash_slice(inner, state);
         }
-    }
-        .into()
-}
 
-// ---------------------------------------------------------------------------
-// Generics helpers
-// ---------------------------------------------------------------------------
+        #[inline]
+        fn portable_hash_slice_unprefixed<H: #hasher_write>(data: &[Self], state: &mut H)
+        where
+            Self: Sized,
+        {
+            #cast
+            <#inner as #hash>::portable_hash_slice_unprefixed(inner, state);
+        }
+    });
+    let hash_impl = match &config.remote {
+        None if config.fallible.is_some() => quote! {
+            impl<#(#lti,)* #(#tpi,)* #(#cpi,)*> #try_hash for #ident<#(#ltt,)* #(#tpt,)* #(#cpt),*> #wc
+            {
+                type Error = #error;
 
-fn fix_where(wc: Option<&mut WhereClause>) -> Option<Token![where]> {
-    if let Some(wc) = wc {
-        if wc.predicates.is_empty() {
-            Some(wc.where_token)
-        } else {
-            if !wc.predicates.trailing_punct() {
-                wc.predicates.push_punct(<Token![,]>::default());
+                #[inline]
+                fn try_portable_hash<H: #hasher_write>(&self, state: &mut H) -> ::core::result::Result<(), Self::Error> {
+                    #tokens
+                    ::core::result::Result::Ok(())
+                }
+            }
+        },
+        Some(remote) => quote! {
+            impl<#(#lti,)* #(#tpi,)* #(#cpi,)*> #root::PortableHashRemote<#remote> for #ident<#(#ltt,)* #(#tpt,)* #(#cpt),*> #wc
+            {
+                #[inline]
+                fn portable_hash_remote<H: #hasher_write>(value: &#remote, state: &mut H) {
+                    #tokens
+                }
+            }
+        },
+        None => quote! {
+            impl<#(#lti,)* #(#tpi,)* #(#cpi,)*> #hash for #ident<#(#ltt,)* #(#tpt,)* #(#cpt),*> #wc
+            {
+                #[inline]
+                fn portable_hash<H: #hasher_write>(&self, state: &mut H) {
+                    #tokens
+                }
+
+                #slice_fn
             }
+        },
+    };
+
+    let layout_impl = layout_check.map(|check| {
+        let SplitGenerics {
+            lti,
+            ltt,
+            tpi,
+            tpt,
+            cpi,
+            cpt,
+            wc,
+        } = split_generics(&input.generics);
+        // Without generics the check can be evaluated right away, instead of when a slice is
+        // first hashed.
+        let eager = if input.generics.params.is_empty() {
+            Some(quote!(const _: () = #ident::__PORTABLE_HASH_TRANSPARENT_LAYOUT;))
+        } else {
             None
+        };
+        quote! {
+            impl<#(#lti,)* #(#tpi,)* #(#cpi,)*> #ident<#(#ltt,)* #(#tpt,)* #(#cpt),*> #wc
+            {
+                #[doc(hidden)]
+                const __PORTABLE_HASH_TRANSPARENT_LAYOUT: () = #check;
+            }
+
+            #eager
         }
-    } else {
-        Some(<Token![where]>::default())
+    });
+
+    quote! {
+        #hash_impl
+        #layout_impl
+        #schema_impl
     }
 }
 
+// ---------------------------------------------------------------------------
+// Generics helpers
+// ---------------------------------------------------------------------------
+
 struct SplitGenerics<
     'a,
     LTI: Iterator<Item = &'a LifetimeParam>,
@@ -713,3 +2346,61 @@ fn split_generics(
         wc: &generics.where_clause,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    /// Snapshot the code generated for each input in `tests/expand`.
+    ///
+    /// Each `tests/expand/<name>.rs` file holds one type deriving `PortableHash`, and the
+    /// formatted expansion is compared against `tests/expand/<name>.expanded.rs`, so changes to
+    /// the generated hash code show up as a diff in review. Set `PORTABLE_HASH_UPDATE=1` to
+    /// write new or changed snapshots.
+    #[test]
+    fn expand_snapshots() {
+        let updating = std::env::var("PORTABLE_HASH_UPDATE")
+            .map(|v| v == "true" || v == "1")
+            .unwrap_or(false);
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/expand");
+
+        let mut inputs: Vec<_> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| {
+                let name = path.file_name().unwrap().to_str().unwrap();
+                name.ends_with(".rs") && !name.ends_with(".expanded.rs")
+            })
+            .collect();
+        inputs.sort();
+        assert!(!inputs.is_empty(), "no expansion inputs found in {}", dir.display());
+
+        let mut failed = Vec::new();
+        for input in &inputs {
+            let source = std::fs::read_to_string(input).unwrap();
+            let derive_input: syn::DeriveInput = syn::parse_str(&source)
+                .unwrap_or_else(|e| panic!("{} is not a single type definition: {}", input.display(), e));
+            let file: syn::File = syn::parse2(super::expand(derive_input)).unwrap();
+            let expanded = prettyplease::unparse(&file);
+
+            let snapshot = input.with_extension("expanded.rs");
+            let expected = std::fs::read_to_string(&snapshot).ok();
+            if expected.as_deref() == Some(expanded.as_str()) {
+                continue;
+            }
+            if updating {
+                println!("updated: {}", snapshot.display());
+                std::fs::write(&snapshot, &expanded).unwrap();
+            } else {
+                println!("changed: {}\n{}", snapshot.display(), expanded);
+                failed.push(snapshot);
+            }
+        }
+
+        assert!(
+            failed.is_empty(),
+            "expansion snapshots changed, review and rerun with PORTABLE_HASH_UPDATE=1: {:?}",
+            failed,
+        );
+    }
+}
@@ -0,0 +1,47 @@
+impl ::portable_hash::TryPortableHash for Sample {
+    type Error = ::portable_hash::HashError;
+    #[inline]
+    fn try_portable_hash<H: ::portable_hash::PortableHasher>(
+        &self,
+        state: &mut H,
+    ) -> ::core::result::Result<(), Self::Error> {
+        match self {
+            Self::Missing => {
+                state.trace_enter(::portable_hash::PathSegment::Variant("Missing"));
+                state.write_u64(0u64);
+                state.trace_exit();
+            }
+            Self::Value(_0) => {
+                state.trace_enter(::portable_hash::PathSegment::Variant("Value"));
+                state.write_u64(1u64);
+                state.trace_enter(::portable_hash::PathSegment::Field("0"));
+                {
+                    let result = ::portable_hash::TryPortableHash::try_portable_hash(
+                        _0,
+                        state,
+                    );
+                    state.trace_exit();
+                    if let ::core::result::Result::Err(error) = result {
+                        state.trace_exit();
+                        return ::core::result::Result::Err(
+                            ::core::convert::From::from(error),
+                        );
+                    }
+                }
+                if let ::core::result::Result::Err(error) = ::portable_hash::PortableHasher::check(
+                    state,
+                ) {
+                    state.trace_exit();
+                    return ::core::result::Result::Err(
+                        ::core::convert::From::from(error),
+                    );
+                }
+                state.trace_exit();
+            }
+        }
+        ::core::result::Result::Ok(())
+    }
+}
+impl ::portable_hash::PortableSchema for Sample {
+    const PORTABLE_HASH_SCHEMA: &'static str = "enum discriminant=index width=u64 { 0: (), 1: (Finite) }";
+}
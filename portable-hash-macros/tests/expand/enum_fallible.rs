@@ -0,0 +1,6 @@
+#[derive(PortableHash)]
+#[portable_hash(fallible, discriminant = "index")]
+enum Sample {
+    Missing,
+    Value(Finite),
+}
@@ -0,0 +1,28 @@
+impl ::portable_hash::PortableHash for Opcode {
+    #[inline]
+    fn portable_hash<H: ::portable_hash::PortableHasher>(&self, state: &mut H) {
+        match self {
+            Self::Nop => {
+                state.trace_enter(::portable_hash::PathSegment::Variant("Nop"));
+                state.write_u8(0u8);
+                state.trace_exit();
+            }
+            Self::Push(_0) => {
+                state.trace_enter(::portable_hash::PathSegment::Variant("Push"));
+                state.write_u8(10u8);
+                state.trace_enter(::portable_hash::PathSegment::Field("0"));
+                ::portable_hash::PortableHash::portable_hash(_0, state);
+                state.trace_exit();
+                state.trace_exit();
+            }
+            Self::Pop => {
+                state.trace_enter(::portable_hash::PathSegment::Variant("Pop"));
+                state.write_u8(42u8);
+                state.trace_exit();
+            }
+        }
+    }
+}
+impl ::portable_hash::PortableSchema for Opcode {
+    const PORTABLE_HASH_SCHEMA: &'static str = "enum discriminant=index width=u8 { 0: (), 10: (u32), 42: () }";
+}
@@ -0,0 +1,8 @@
+#[derive(PortableHash)]
+#[portable_hash(discriminant = "index", discriminant_width = "u8")]
+enum Opcode {
+    Nop,
+    Push(u32) = 10,
+    #[portable_hash(discriminant = 42)]
+    Pop,
+}
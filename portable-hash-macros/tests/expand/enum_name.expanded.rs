@@ -0,0 +1,37 @@
+impl<T> ::portable_hash::PortableHash for Payment<T>
+where
+    T: ::portable_hash::PortableHash,
+{
+    #[inline]
+    fn portable_hash<H: ::portable_hash::PortableHasher>(&self, state: &mut H) {
+        match self {
+            Self::Pending => {
+                state.trace_enter(::portable_hash::PathSegment::Variant("Pending"));
+                state.write_u64(17248396201904143176u64);
+                state.trace_exit();
+            }
+            Self::Paid { amount, receipt: _ } => {
+                state.trace_enter(::portable_hash::PathSegment::Variant("Paid"));
+                state.write_u64(8765490556988924174u64);
+                state.trace_enter(::portable_hash::PathSegment::Field("amount"));
+                ::portable_hash::PortableHash::portable_hash(amount, state);
+                state.trace_exit();
+                state.trace_exit();
+            }
+            Self::Refunded(_0, _1) => {
+                state.trace_enter(::portable_hash::PathSegment::Variant("Refunded"));
+                state.write_u64(14839356020154877826u64);
+                state.trace_enter(::portable_hash::PathSegment::Field("0"));
+                ::portable_hash::PortableHash::portable_hash(_0, state);
+                state.trace_exit();
+                state.trace_enter(::portable_hash::PathSegment::Field("1"));
+                ::portable_hash::PortableHash::portable_hash(_1, state);
+                state.trace_exit();
+                state.trace_exit();
+            }
+        }
+    }
+}
+impl<T> ::portable_hash::PortableSchema for Payment<T> {
+    const PORTABLE_HASH_SCHEMA: &'static str = "enum discriminant=name width=u64 { 8765490556988924174: (u64), 14839356020154877826: (T, u64), 17248396201904143176: () }";
+}
@@ -0,0 +1,8 @@
+#[derive(PortableHash)]
+#[portable_hash(namespace, retired = ["Cancelled"])]
+enum Payment<T> {
+    Pending,
+    #[portable_hash(rename = "Settled", alias = "Complete")]
+    Paid { amount: u64, #[portable_hash(skip)] receipt: Receipt },
+    Refunded(T, u64),
+}
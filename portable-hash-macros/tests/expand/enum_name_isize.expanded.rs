@@ -0,0 +1,23 @@
+impl ::portable_hash::PortableHash for Payment {
+    #[inline]
+    fn portable_hash<H: ::portable_hash::PortableHasher>(&self, state: &mut H) {
+        match self {
+            Self::Pending => {
+                state.trace_enter(::portable_hash::PathSegment::Variant("Pending"));
+                state.write_i64(8674895585303826588i64);
+                state.trace_exit();
+            }
+            Self::Paid(_0) => {
+                state.trace_enter(::portable_hash::PathSegment::Variant("Paid"));
+                state.write_i64(3312177734955379879i64);
+                state.trace_enter(::portable_hash::PathSegment::Field("0"));
+                ::portable_hash::PortableHash::portable_hash(_0, state);
+                state.trace_exit();
+                state.trace_exit();
+            }
+        }
+    }
+}
+impl ::portable_hash::PortableSchema for Payment {
+    const PORTABLE_HASH_SCHEMA: &'static str = "enum discriminant=name width=isize { 3312177734955379879: (u64), 8674895585303826588: () }";
+}
@@ -0,0 +1,6 @@
+#[derive(PortableHash)]
+#[portable_hash(discriminant_width = "isize")]
+enum Payment {
+    Pending,
+    Paid(u64),
+}
@@ -0,0 +1,28 @@
+impl ::portable_hash::PortableHash for Payment {
+    #[inline]
+    fn portable_hash<H: ::portable_hash::PortableHasher>(&self, state: &mut H) {
+        match self {
+            Self::Pending => {
+                state.trace_enter(::portable_hash::PathSegment::Variant("Pending"));
+                state.write_u16(17102u16);
+                state.trace_exit();
+            }
+            Self::Paid(_0) => {
+                state.trace_enter(::portable_hash::PathSegment::Variant("Paid"));
+                state.write_u16(54704u16);
+                state.trace_enter(::portable_hash::PathSegment::Field("0"));
+                ::portable_hash::PortableHash::portable_hash(_0, state);
+                state.trace_exit();
+                state.trace_exit();
+            }
+            Self::Refunded => {
+                state.trace_enter(::portable_hash::PathSegment::Variant("Refunded"));
+                state.write_u16(7u16);
+                state.trace_exit();
+            }
+        }
+    }
+}
+impl ::portable_hash::PortableSchema for Payment {
+    const PORTABLE_HASH_SCHEMA: &'static str = "enum discriminant=name width=u16 { 7: (), 17102: (), 54704: (u64) }";
+}
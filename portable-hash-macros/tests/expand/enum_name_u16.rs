@@ -0,0 +1,9 @@
+#[derive(PortableHash)]
+#[portable_hash(discriminant_width = "u16", retired = ["Cancelled"])]
+enum Payment {
+    Pending,
+    #[portable_hash(alias = "Complete")]
+    Paid(u64),
+    #[portable_hash(discriminant = 7)]
+    Refunded,
+}
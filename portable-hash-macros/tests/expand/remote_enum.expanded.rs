@@ -0,0 +1,35 @@
+impl<L, R> ::portable_hash::PortableHashRemote<other::Either<L, R>> for EitherDef<L, R>
+where
+    L: ::portable_hash::PortableHash,
+    R: ::portable_hash::PortableHash,
+{
+    #[inline]
+    fn portable_hash_remote<H: ::portable_hash::PortableHasher>(
+        value: &other::Either<L, R>,
+        state: &mut H,
+    ) {
+        match value {
+            other::Either::Left(_0) => {
+                state.trace_enter(::portable_hash::PathSegment::Variant("Left"));
+                state.write_u64(11944515657769805200u64);
+                let _: &L = _0;
+                state.trace_enter(::portable_hash::PathSegment::Field("0"));
+                ::portable_hash::PortableHash::portable_hash(_0, state);
+                state.trace_exit();
+                state.trace_exit();
+            }
+            other::Either::Right { value } => {
+                state.trace_enter(::portable_hash::PathSegment::Variant("Right"));
+                state.write_u64(3920825177732577125u64);
+                let _: &R = value;
+                state.trace_enter(::portable_hash::PathSegment::Field("value"));
+                ::portable_hash::PortableHash::portable_hash(value, state);
+                state.trace_exit();
+                state.trace_exit();
+            }
+        }
+    }
+}
+impl<L, R> ::portable_hash::PortableSchema for EitherDef<L, R> {
+    const PORTABLE_HASH_SCHEMA: &'static str = "enum discriminant=name width=u64 { 3920825177732577125: (R), 11944515657769805200: (L) }";
+}
@@ -0,0 +1,6 @@
+#[derive(PortableHash)]
+#[portable_hash(remote = "other::Either<L, R>")]
+enum EitherDef<L, R> {
+    Left(L),
+    Right { value: R },
+}
@@ -0,0 +1,34 @@
+impl<T> my_sdk::hash::PortableHash for Frame<T>
+where
+    T: PortableHash + Ord,
+{
+    #[inline]
+    fn portable_hash<H: my_sdk::hash::PortableHasher>(&self, state: &mut H) {
+        state.trace_enter(my_sdk::hash::PathSegment::Field("len"));
+        {
+            #[allow(clippy::useless_conversion)]
+            let encoded = <::core::primitive::u64 as ::core::convert::From<
+                u32,
+            >>::from(*&self.len);
+            my_sdk::hash::encoding::write_varint(state, encoded);
+        }
+        state.trace_exit();
+        state.trace_enter(my_sdk::hash::PathSegment::Field("kind"));
+        <u16 as my_sdk::hash::encoding::BigEndian>::write_be(&self.kind, state);
+        state.trace_exit();
+        state.trace_enter(my_sdk::hash::PathSegment::Field("checksum"));
+        my_sdk::hash::encoding::hash_fixed_len(&self.checksum, state);
+        state.trace_exit();
+        state.trace_enter(my_sdk::hash::PathSegment::Field("origin"));
+        <PointDef as my_sdk::hash::PortableHashRemote<
+            _,
+        >>::portable_hash_remote(&self.origin, state);
+        state.trace_exit();
+        state.trace_enter(my_sdk::hash::PathSegment::Field("payload"));
+        my_sdk::hash::PortableHash::portable_hash(&self.payload, state);
+        state.trace_exit();
+    }
+}
+impl<T> my_sdk::hash::PortableSchema for Frame<T> {
+    const PORTABLE_HASH_SCHEMA: &'static str = "struct (encoding(varint) u32, encoding(be) u16, encoding(fixed_len) [u8; 4], with(PointDef) other::Point, T)";
+}
@@ -0,0 +1,16 @@
+#[derive(PortableHash)]
+#[portable_hash(crate = "my_sdk::hash")]
+struct Frame<T> {
+    #[portable_hash(encoding = "varint")]
+    len: u32,
+    #[portable_hash(encoding = "be")]
+    kind: u16,
+    #[portable_hash(encoding = "fixed_len")]
+    checksum: [u8; 4],
+    #[portable_hash(with = "PointDef")]
+    origin: other::Point,
+    #[portable_hash(bound = "T: PortableHash + Ord")]
+    payload: T,
+    #[portable_hash(skip)]
+    cache: Cache,
+}
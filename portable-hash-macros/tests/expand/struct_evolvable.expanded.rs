@@ -0,0 +1,48 @@
+impl<T> ::portable_hash::PortableHash for Order<T>
+where
+    T: ::portable_hash::PortableHash,
+    Vec<T>: ::core::default::Default + ::core::cmp::PartialEq,
+{
+    #[inline]
+    fn portable_hash<H: ::portable_hash::PortableHasher>(&self, state: &mut H) {
+        state.trace_enter(::portable_hash::PathSegment::Field("id"));
+        if !<u64 as ::core::cmp::PartialEq>::eq(
+            &self.id,
+            &<u64 as ::core::default::Default>::default(),
+        ) {
+            state.write_u64(628021283683842752u64);
+            ::portable_hash::PortableHash::portable_hash(&self.id, state);
+        }
+        state.trace_exit();
+        state.trace_enter(::portable_hash::PathSegment::Field("items"));
+        if !<Vec<
+            T,
+        > as ::core::cmp::PartialEq>::eq(
+            &self.items,
+            &<Vec<T> as ::core::default::Default>::default(),
+        ) {
+            state.write_u64(4501493784760101999u64);
+            ::portable_hash::PortableHash::portable_hash(&self.items, state);
+        }
+        state.trace_exit();
+        state.trace_enter(::portable_hash::PathSegment::Field("priority"));
+        if !<u32 as ::core::cmp::PartialEq>::eq(
+            &self.priority,
+            &<u32 as ::core::default::Default>::default(),
+        ) {
+            state.write_u64(17705596872970477193u64);
+            {
+                #[allow(clippy::useless_conversion)]
+                let encoded = <::core::primitive::u64 as ::core::convert::From<
+                    u32,
+                >>::from(*&self.priority);
+                ::portable_hash::encoding::write_varint(state, encoded);
+            }
+        }
+        state.trace_exit();
+        state.write_u64(14695981039346656037u64);
+    }
+}
+impl<T> ::portable_hash::PortableSchema for Order<T> {
+    const PORTABLE_HASH_SCHEMA: &'static str = "evolvable struct (skip_if_default(id) u64, skip_if_default(items) Vec<T>, skip_if_default(priority) encoding(varint) u32)";
+}
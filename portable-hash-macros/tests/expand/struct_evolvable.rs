@@ -0,0 +1,8 @@
+#[derive(PortableHash)]
+#[portable_hash(evolvable)]
+struct Order<T> {
+    id: u64,
+    items: Vec<T>,
+    #[portable_hash(encoding = "varint")]
+    priority: u32,
+}
@@ -0,0 +1,57 @@
+impl<T> ::portable_hash::TryPortableHash for Reading<T>
+where
+    T: ::portable_hash::TryPortableHash,
+    ReadingError: ::core::convert::From<<T as ::portable_hash::TryPortableHash>::Error>,
+{
+    type Error = ReadingError;
+    #[inline]
+    fn try_portable_hash<H: ::portable_hash::PortableHasher>(
+        &self,
+        state: &mut H,
+    ) -> ::core::result::Result<(), Self::Error> {
+        state.trace_enter(::portable_hash::PathSegment::Field("sensor"));
+        {
+            let result = ::portable_hash::TryPortableHash::try_portable_hash(
+                &self.sensor,
+                state,
+            );
+            state.trace_exit();
+            if let ::core::result::Result::Err(error) = result {
+                return ::core::result::Result::Err(::core::convert::From::from(error));
+            }
+        }
+        if let ::core::result::Result::Err(error) = ::portable_hash::PortableHasher::check(
+            state,
+        ) {
+            return ::core::result::Result::Err(::core::convert::From::from(error));
+        }
+        state.trace_enter(::portable_hash::PathSegment::Field("kind"));
+        <u16 as ::portable_hash::encoding::BigEndian>::write_be(&self.kind, state);
+        state.trace_exit();
+        if let ::core::result::Result::Err(error) = ::portable_hash::PortableHasher::check(
+            state,
+        ) {
+            return ::core::result::Result::Err(::core::convert::From::from(error));
+        }
+        state.trace_enter(::portable_hash::PathSegment::Field("value"));
+        {
+            let result = ::portable_hash::TryPortableHash::try_portable_hash(
+                &self.value,
+                state,
+            );
+            state.trace_exit();
+            if let ::core::result::Result::Err(error) = result {
+                return ::core::result::Result::Err(::core::convert::From::from(error));
+            }
+        }
+        if let ::core::result::Result::Err(error) = ::portable_hash::PortableHasher::check(
+            state,
+        ) {
+            return ::core::result::Result::Err(::core::convert::From::from(error));
+        }
+        ::core::result::Result::Ok(())
+    }
+}
+impl<T> ::portable_hash::PortableSchema for Reading<T> {
+    const PORTABLE_HASH_SCHEMA: &'static str = "struct (u32, encoding(be) u16, T)";
+}
@@ -0,0 +1,8 @@
+#[derive(PortableHash)]
+#[portable_hash(fallible = "ReadingError")]
+struct Reading<T> {
+    sensor: u32,
+    #[portable_hash(encoding = "be")]
+    kind: u16,
+    value: T,
+}
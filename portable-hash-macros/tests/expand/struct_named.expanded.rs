@@ -0,0 +1,17 @@
+impl ::portable_hash::PortableHash for Order {
+    #[inline]
+    fn portable_hash<H: ::portable_hash::PortableHasher>(&self, state: &mut H) {
+        state.trace_enter(::portable_hash::PathSegment::Field("id"));
+        ::portable_hash::PortableHash::portable_hash(&self.id, state);
+        state.trace_exit();
+        state.trace_enter(::portable_hash::PathSegment::Field("items"));
+        ::portable_hash::PortableHash::portable_hash(&self.items, state);
+        state.trace_exit();
+        state.trace_enter(::portable_hash::PathSegment::Field("note"));
+        ::portable_hash::PortableHash::portable_hash(&self.note, state);
+        state.trace_exit();
+    }
+}
+impl ::portable_hash::PortableSchema for Order {
+    const PORTABLE_HASH_SCHEMA: &'static str = "struct (u64, [(u32, u8); 4], &'static str)";
+}
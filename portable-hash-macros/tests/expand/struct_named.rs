@@ -0,0 +1,6 @@
+#[derive(PortableHash)]
+struct Order {
+    id: u64,
+    items: [(u32, u8); 4],
+    note: &'static str,
+}
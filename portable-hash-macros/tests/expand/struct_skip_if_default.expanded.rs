@@ -0,0 +1,20 @@
+impl ::portable_hash::PortableHash for Order {
+    #[inline]
+    fn portable_hash<H: ::portable_hash::PortableHasher>(&self, state: &mut H) {
+        state.trace_enter(::portable_hash::PathSegment::Field("id"));
+        ::portable_hash::PortableHash::portable_hash(&self.id, state);
+        state.trace_exit();
+        state.trace_enter(::portable_hash::PathSegment::Field("note"));
+        if !<String as ::core::cmp::PartialEq>::eq(
+            &self.note,
+            &<String as ::core::default::Default>::default(),
+        ) {
+            state.write_u64(4321480622256717021u64);
+            ::portable_hash::PortableHash::portable_hash(&self.note, state);
+        }
+        state.trace_exit();
+    }
+}
+impl ::portable_hash::PortableSchema for Order {
+    const PORTABLE_HASH_SCHEMA: &'static str = "struct (u64, skip_if_default(note) String)";
+}
@@ -0,0 +1,6 @@
+#[derive(PortableHash)]
+struct Order {
+    id: u64,
+    #[portable_hash(skip_if_default)]
+    note: String,
+}
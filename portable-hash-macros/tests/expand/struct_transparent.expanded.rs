@@ -0,0 +1,51 @@
+impl ::portable_hash::PortableHash for UserId {
+    #[inline]
+    fn portable_hash<H: ::portable_hash::PortableHasher>(&self, state: &mut H) {
+        ::portable_hash::PortableHash::portable_hash(&self.id, state);
+    }
+    #[inline]
+    fn portable_hash_slice<H: ::portable_hash::PortableHasher>(
+        data: &[Self],
+        state: &mut H,
+    )
+    where
+        Self: Sized,
+    {
+        let () = Self::__PORTABLE_HASH_TRANSPARENT_LAYOUT;
+        #[allow(unsafe_code)]
+        let inner = unsafe {
+            ::core::slice::from_raw_parts(data.as_ptr() as *const u64, data.len())
+        };
+        <u64 as ::portable_hash::PortableHash>::portable_hash_slice(inner, state);
+    }
+    #[inline]
+    fn portable_hash_slice_unprefixed<H: ::portable_hash::PortableHasher>(
+        data: &[Self],
+        state: &mut H,
+    )
+    where
+        Self: Sized,
+    {
+        let () = Self::__PORTABLE_HASH_TRANSPARENT_LAYOUT;
+        #[allow(unsafe_code)]
+        let inner = unsafe {
+            ::core::slice::from_raw_parts(data.as_ptr() as *const u64, data.len())
+        };
+        <u64 as ::portable_hash::PortableHash>::portable_hash_slice_unprefixed(
+            inner,
+            state,
+        );
+    }
+}
+impl UserId {
+    #[doc(hidden)]
+    const __PORTABLE_HASH_TRANSPARENT_LAYOUT: () = assert!(
+        ::core::mem::size_of:: < Self > () == ::core::mem::size_of:: < u64 > () &&
+        ::core::mem::align_of:: < Self > () == ::core::mem::align_of:: < u64 > (),
+        "`transparent` field must be the only field with a non-zero size",
+    );
+}
+const _: () = UserId::__PORTABLE_HASH_TRANSPARENT_LAYOUT;
+impl ::portable_hash::PortableSchema for UserId {
+    const PORTABLE_HASH_SCHEMA: &'static str = "transparent u64";
+}
@@ -0,0 +1,8 @@
+#[derive(PortableHash)]
+#[repr(transparent)]
+#[portable_hash(transparent)]
+struct UserId {
+    id: u64,
+    #[portable_hash(skip)]
+    marker: core::marker::PhantomData<User>,
+}
@@ -0,0 +1,20 @@
+impl<'a, T, const N: usize> ::portable_hash::PortableHash for Labelled<'a, T, N>
+where
+    T: ::portable_hash::PortableHash,
+{
+    #[inline]
+    fn portable_hash<H: ::portable_hash::PortableHasher>(&self, state: &mut H) {
+        state.trace_enter(::portable_hash::PathSegment::Field("0"));
+        ::portable_hash::PortableHash::portable_hash(&self.0, state);
+        state.trace_exit();
+        state.trace_enter(::portable_hash::PathSegment::Field("1"));
+        ::portable_hash::PortableHash::portable_hash(&self.1, state);
+        state.trace_exit();
+        state.trace_enter(::portable_hash::PathSegment::Field("2"));
+        ::portable_hash::PortableHash::portable_hash(&self.2, state);
+        state.trace_exit();
+    }
+}
+impl<'a, T, const N: usize> ::portable_hash::PortableSchema for Labelled<'a, T, N> {
+    const PORTABLE_HASH_SCHEMA: &'static str = "struct (&'a str, [T; N], core::marker::PhantomData<T>)";
+}
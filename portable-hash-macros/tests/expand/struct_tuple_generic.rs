@@ -0,0 +1,2 @@
+#[derive(PortableHash)]
+struct Labelled<'a, T, const N: usize>(&'a str, [T; N], core::marker::PhantomData<T>);
@@ -0,0 +1,32 @@
+impl ::portable_hash::PortableHash for Event {
+    #[inline]
+    fn portable_hash<H: ::portable_hash::PortableHasher>(&self, state: &mut H) {
+        fn assert_union_contract<T: ?Sized + ::portable_hash::PortableHashUnion>() {}
+        assert_union_contract::<Self>();
+        #[allow(unsafe_code)]
+        let tag = unsafe { &self.tag };
+        state.trace_enter(::portable_hash::PathSegment::Field("tag"));
+        ::portable_hash::PortableHash::portable_hash(tag, state);
+        state.trace_exit();
+        match *tag {
+            1 => {
+                #[allow(unsafe_code)]
+                let value = unsafe { &self.key };
+                state.trace_enter(::portable_hash::PathSegment::Field("key"));
+                ::portable_hash::PortableHash::portable_hash(value, state);
+                state.trace_exit();
+            }
+            2 => {
+                #[allow(unsafe_code)]
+                let value = unsafe { &self.mouse };
+                state.trace_enter(::portable_hash::PathSegment::Field("mouse"));
+                ::portable_hash::PortableHash::portable_hash(value, state);
+                state.trace_exit();
+            }
+            _ => {}
+        }
+    }
+}
+impl ::portable_hash::PortableSchema for Event {
+    const PORTABLE_HASH_SCHEMA: &'static str = "union tag=u8 { 1: KeyEvent, 2: MouseEvent }";
+}
@@ -0,0 +1,8 @@
+#[derive(PortableHash)]
+#[repr(C)]
+#[portable_hash(union_tag = "tag", variants(key = 1, mouse = 2))]
+union Event {
+    tag: u8,
+    key: KeyEvent,
+    mouse: MouseEvent,
+}
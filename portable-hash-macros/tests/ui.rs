@@ -0,0 +1,11 @@
+//! Compile-fail tests for the errors reported by `#[derive(PortableHash)]`.
+//!
+//! Each file in `tests/ui` must fail to compile with the error in its `.stderr` file. After an
+//! intentional change to an error message, regenerate the `.stderr` files with
+//! `TRYBUILD=overwrite cargo test -p portable-hash-macros --test ui`.
+
+#[test]
+fn ui() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+}
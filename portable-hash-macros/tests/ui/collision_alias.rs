@@ -0,0 +1,10 @@
+use portable_hash::PortableHash;
+
+#[derive(PortableHash)]
+enum Status {
+    #[portable_hash(alias = "Enabled")]
+    Active,
+    Enabled,
+}
+
+fn main() {}
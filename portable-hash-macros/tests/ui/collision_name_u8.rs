@@ -0,0 +1,12 @@
+use portable_hash::PortableHash;
+
+// "Inactive" and "Error" only collide once their name hashes are folded to 8 bits.
+#[derive(PortableHash)]
+#[portable_hash(discriminant_width = "u8")]
+enum Status {
+    Active,
+    Inactive,
+    Error,
+}
+
+fn main() {}
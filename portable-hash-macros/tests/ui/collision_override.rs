@@ -0,0 +1,11 @@
+use portable_hash::PortableHash;
+
+#[derive(PortableHash)]
+#[portable_hash(discriminant = "index")]
+enum Status {
+    Active,
+    #[portable_hash(discriminant = 0)]
+    Enabled,
+}
+
+fn main() {}
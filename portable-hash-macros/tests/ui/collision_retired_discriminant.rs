@@ -0,0 +1,10 @@
+use portable_hash::PortableHash;
+
+#[derive(PortableHash)]
+#[portable_hash(discriminant = "index", retired = [1])]
+enum Status {
+    Active,
+    Enabled,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use portable_hash::PortableHash;
+
+#[derive(PortableHash)]
+#[portable_hash(retired = ["Active"])]
+enum Status {
+    Active,
+}
+
+fn main() {}
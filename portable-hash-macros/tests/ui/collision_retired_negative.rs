@@ -0,0 +1,10 @@
+use portable_hash::PortableHash;
+
+#[derive(PortableHash)]
+#[portable_hash(discriminant = "index", discriminant_width = "isize", retired = [-1])]
+enum Status {
+    Removed = -1,
+    Active = 0,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use portable_hash::PortableHash;
+
+#[derive(PortableHash)]
+enum Status {
+    Active,
+    #[portable_hash(rename = "Active")]
+    Enabled,
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+use portable_hash::PortableHash;
+
+#[derive(PortableHash)]
+#[portable_hash(bound = 1)]
+struct Wrapper<T>(T);
+
+fn main() {}
@@ -0,0 +1,7 @@
+use portable_hash::PortableHash;
+
+#[derive(PortableHash)]
+#[portable_hash(crate = 1)]
+struct Point(u32);
+
+fn main() {}
@@ -0,0 +1,7 @@
+use portable_hash::PortableHash;
+
+#[derive(PortableHash)]
+#[portable_hash(remote = 1)]
+struct PointDef(u32);
+
+fn main() {}
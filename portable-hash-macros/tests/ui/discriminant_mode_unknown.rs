@@ -0,0 +1,9 @@
+use portable_hash::PortableHash;
+
+#[derive(PortableHash)]
+#[portable_hash(discriminant = "position")]
+enum Status {
+    Active,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use portable_hash::PortableHash;
+
+#[derive(PortableHash)]
+#[portable_hash(discriminant_width = "u128")]
+enum Status {
+    Active,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use portable_hash::PortableHash;
+
+#[derive(PortableHash)]
+#[portable_hash(evolvable)]
+enum Status {
+    Active,
+    Inactive,
+}
+
+fn main() {}
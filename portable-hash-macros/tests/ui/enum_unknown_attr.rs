@@ -0,0 +1,9 @@
+use portable_hash::PortableHash;
+
+#[derive(PortableHash)]
+#[portable_hash(rename_all = "snake_case")]
+enum Status {
+    Active,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use portable_hash::PortableHash;
+
+#[derive(PortableHash)]
+#[portable_hash(fallible = 1)]
+struct Reading {
+    value: u32,
+}
+
+fn main() {}
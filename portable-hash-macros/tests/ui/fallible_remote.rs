@@ -0,0 +1,16 @@
+use portable_hash::PortableHash;
+
+mod other {
+    pub struct Point {
+        pub x: i32,
+    }
+}
+
+#[derive(PortableHash)]
+#[portable_hash(remote = "other::Point", fallible)]
+#[allow(dead_code)]
+struct PointDef {
+    x: i32,
+}
+
+fn main() {}
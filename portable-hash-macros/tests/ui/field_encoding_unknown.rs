@@ -0,0 +1,9 @@
+use portable_hash::PortableHash;
+
+#[derive(PortableHash)]
+struct Frame {
+    #[portable_hash(encoding = "zigzag")]
+    len: i32,
+}
+
+fn main() {}
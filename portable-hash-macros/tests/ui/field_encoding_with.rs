@@ -0,0 +1,9 @@
+use portable_hash::PortableHash;
+
+#[derive(PortableHash)]
+struct Frame {
+    #[portable_hash(encoding = "varint", with = "LenDef")]
+    len: u32,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use portable_hash::PortableHash;
+
+#[derive(PortableHash)]
+struct Cached {
+    id: u64,
+    #[portable_hash(skip, skip_if_default)]
+    cache: u64,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use portable_hash::PortableHash;
+
+#[derive(PortableHash)]
+struct Frame {
+    #[portable_hash(skip, with = "LenDef")]
+    len: u32,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use portable_hash::PortableHash;
+
+#[derive(PortableHash)]
+struct Point {
+    #[portable_hash(rename = "y")]
+    x: u32,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use portable_hash::PortableHash;
+
+#[derive(PortableHash)]
+struct Point {
+    #[portable_hash(with = 1)]
+    x: u32,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use portable_hash::PortableHash;
+
+#[derive(PortableHash)]
+#[portable_hash(discriminant = "index", namespace)]
+enum Status {
+    Active,
+}
+
+fn main() {}
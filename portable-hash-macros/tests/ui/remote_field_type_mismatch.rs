@@ -0,0 +1,18 @@
+use portable_hash::PortableHash;
+
+mod other {
+    pub struct Point {
+        pub x: i64,
+        pub y: i64,
+    }
+}
+
+#[derive(PortableHash)]
+#[portable_hash(remote = "other::Point")]
+#[allow(dead_code)]
+struct PointDef {
+    x: u32,
+    y: i64,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use portable_hash::PortableHash;
+
+#[derive(PortableHash)]
+#[portable_hash(retired = [18446744073709551616])]
+enum Status {
+    Active,
+}
+
+fn main() {}
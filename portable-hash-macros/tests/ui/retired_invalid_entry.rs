@@ -0,0 +1,9 @@
+use portable_hash::PortableHash;
+
+#[derive(PortableHash)]
+#[portable_hash(retired = [1.5])]
+enum Status {
+    Active,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use portable_hash::PortableHash;
+
+#[derive(PortableHash)]
+#[portable_hash(discriminant = "index", retired = ["Removed"])]
+enum Status {
+    Active,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use portable_hash::PortableHash;
+
+const BASE: isize = 4;
+
+#[derive(PortableHash)]
+#[portable_hash(discriminant = "index")]
+enum Status {
+    Active = BASE,
+}
+
+fn main() {}
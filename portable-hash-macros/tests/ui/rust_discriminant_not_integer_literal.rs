@@ -0,0 +1,9 @@
+use portable_hash::PortableHash;
+
+#[derive(PortableHash)]
+#[portable_hash(discriminant = "index")]
+enum Status {
+    Active = 'a',
+}
+
+fn main() {}
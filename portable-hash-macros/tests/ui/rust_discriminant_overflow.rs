@@ -0,0 +1,9 @@
+use portable_hash::PortableHash;
+
+#[derive(PortableHash)]
+#[portable_hash(discriminant = "index")]
+enum Status {
+    Active = 170141183460469231731687303715884105728,
+}
+
+fn main() {}
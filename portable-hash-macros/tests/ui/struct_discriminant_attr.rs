@@ -0,0 +1,7 @@
+use portable_hash::PortableHash;
+
+#[derive(PortableHash)]
+#[portable_hash(discriminant = "index")]
+struct Point(u32);
+
+fn main() {}
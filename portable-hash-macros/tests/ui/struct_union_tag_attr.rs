@@ -0,0 +1,7 @@
+use portable_hash::PortableHash;
+
+#[derive(PortableHash)]
+#[portable_hash(union_tag = "tag")]
+struct Point(u32);
+
+fn main() {}
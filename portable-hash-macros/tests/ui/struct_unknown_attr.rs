@@ -0,0 +1,7 @@
+use portable_hash::PortableHash;
+
+#[derive(PortableHash)]
+#[portable_hash(rename_all = "snake_case")]
+struct Point(u32);
+
+fn main() {}
@@ -0,0 +1,8 @@
+use portable_hash::PortableHash;
+
+#[derive(PortableHash)]
+#[repr(transparent)]
+#[portable_hash(transparent)]
+struct Length(#[portable_hash(encoding = "varint")] u32);
+
+fn main() {}
@@ -0,0 +1,12 @@
+use portable_hash::PortableHash;
+
+mod other {
+    pub struct UserId(pub u64);
+}
+
+#[derive(PortableHash)]
+#[repr(transparent)]
+#[portable_hash(transparent, remote = "other::UserId")]
+struct UserIdDef(u64);
+
+fn main() {}
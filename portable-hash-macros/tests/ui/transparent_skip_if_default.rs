@@ -0,0 +1,8 @@
+use portable_hash::PortableHash;
+
+#[derive(PortableHash)]
+#[repr(transparent)]
+#[portable_hash(transparent)]
+struct UserId(#[portable_hash(skip_if_default)] u64);
+
+fn main() {}
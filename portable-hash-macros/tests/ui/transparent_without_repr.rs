@@ -0,0 +1,7 @@
+use portable_hash::PortableHash;
+
+#[derive(PortableHash)]
+#[portable_hash(transparent)]
+struct UserId(u64);
+
+fn main() {}
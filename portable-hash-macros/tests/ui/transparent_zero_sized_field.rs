@@ -0,0 +1,12 @@
+use portable_hash::PortableHash;
+
+#[derive(PortableHash)]
+#[repr(transparent)]
+#[portable_hash(transparent)]
+struct Marker {
+    tag: (),
+    #[portable_hash(skip)]
+    data: u64,
+}
+
+fn main() {}
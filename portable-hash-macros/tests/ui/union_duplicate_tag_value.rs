@@ -0,0 +1,12 @@
+use portable_hash::PortableHash;
+
+#[derive(PortableHash)]
+#[repr(C)]
+#[portable_hash(union_tag = "tag", variants(int = 1, float = 1))]
+union Value {
+    tag: u8,
+    int: u32,
+    float: f32,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use portable_hash::PortableHash;
+
+#[derive(PortableHash)]
+#[repr(C)]
+#[portable_hash(union_tag = "tag", variants(value = 1))]
+union Event {
+    tag: u8,
+    value: (u8, u32),
+}
+
+fn main() {}
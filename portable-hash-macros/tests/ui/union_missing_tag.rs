@@ -0,0 +1,10 @@
+use portable_hash::PortableHash;
+
+#[derive(PortableHash)]
+#[repr(C)]
+union Value {
+    int: u32,
+    float: f32,
+}
+
+fn main() {}
@@ -0,0 +1,20 @@
+use portable_hash::PortableHash;
+
+mod other {
+    #[repr(C)]
+    pub union Tagged {
+        pub tag: u8,
+        pub value: u32,
+    }
+}
+
+#[derive(PortableHash)]
+#[repr(C)]
+#[portable_hash(union_tag = "tag", variants(value = 1), remote = "other::Tagged")]
+#[allow(dead_code)]
+union TaggedDef {
+    tag: u8,
+    value: u32,
+}
+
+fn main() {}
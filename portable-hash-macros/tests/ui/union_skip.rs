@@ -0,0 +1,13 @@
+use portable_hash::PortableHash;
+
+#[derive(PortableHash)]
+#[repr(C)]
+#[portable_hash(union_tag = "tag", variants(int = 1))]
+union Value {
+    tag: u8,
+    int: u32,
+    #[portable_hash(skip)]
+    float: f32,
+}
+
+fn main() {}
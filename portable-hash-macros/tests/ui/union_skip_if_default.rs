@@ -0,0 +1,12 @@
+use portable_hash::PortableHash;
+
+#[derive(PortableHash)]
+#[repr(C)]
+#[portable_hash(union_tag = "tag", variants(int = 1))]
+union Value {
+    tag: u8,
+    #[portable_hash(skip_if_default)]
+    int: u32,
+}
+
+fn main() {}
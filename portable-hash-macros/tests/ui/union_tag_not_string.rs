@@ -0,0 +1,10 @@
+use portable_hash::PortableHash;
+
+#[derive(PortableHash)]
+#[portable_hash(union_tag = 1, variants(int = 1))]
+union Value {
+    tag: u8,
+    int: u32,
+}
+
+fn main() {}
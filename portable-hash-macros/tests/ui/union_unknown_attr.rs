@@ -0,0 +1,9 @@
+use portable_hash::PortableHash;
+
+#[derive(PortableHash)]
+#[portable_hash(rename_all = "snake_case")]
+union Value {
+    int: u32,
+}
+
+fn main() {}
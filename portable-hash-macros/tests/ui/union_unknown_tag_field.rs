@@ -0,0 +1,11 @@
+use portable_hash::PortableHash;
+
+#[derive(PortableHash)]
+#[repr(C)]
+#[portable_hash(union_tag = "kind", variants(int = 1))]
+union Value {
+    tag: u8,
+    int: u32,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use portable_hash::PortableHash;
+
+#[derive(PortableHash)]
+#[portable_hash(discriminant = "index")]
+enum Status {
+    #[portable_hash(alias = "Enabled")]
+    Active,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use portable_hash::PortableHash;
+
+#[derive(PortableHash)]
+enum Status {
+    #[portable_hash(alias = 1)]
+    Active,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use portable_hash::PortableHash;
+
+#[derive(PortableHash)]
+enum Status {
+    #[portable_hash(discriminant = "one")]
+    Active,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use portable_hash::PortableHash;
+
+#[derive(PortableHash)]
+enum Status {
+    #[portable_hash(discriminant = 18446744073709551616)]
+    Active,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use portable_hash::PortableHash;
+
+#[derive(PortableHash)]
+#[portable_hash(discriminant = "index")]
+enum Status {
+    #[portable_hash(rename = "Enabled")]
+    Active,
+}
+
+fn main() {}
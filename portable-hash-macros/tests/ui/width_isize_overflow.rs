@@ -0,0 +1,10 @@
+use portable_hash::PortableHash;
+
+#[derive(PortableHash)]
+#[repr(u64)]
+#[portable_hash(discriminant = "index", discriminant_width = "isize")]
+enum Status {
+    Active = 18446744073709551615,
+}
+
+fn main() {}
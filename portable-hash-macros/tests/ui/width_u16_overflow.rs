@@ -0,0 +1,9 @@
+use portable_hash::PortableHash;
+
+#[derive(PortableHash)]
+#[portable_hash(discriminant = "index", discriminant_width = "u16")]
+enum Status {
+    Active = 65536,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use portable_hash::PortableHash;
+
+#[derive(PortableHash)]
+#[repr(u64)]
+#[portable_hash(discriminant = "index", discriminant_width = "u32")]
+enum Status {
+    Active = 4294967296,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use portable_hash::PortableHash;
+
+#[derive(PortableHash)]
+#[portable_hash(discriminant = "index", discriminant_width = "u64")]
+enum Status {
+    Active = -1,
+}
+
+fn main() {}
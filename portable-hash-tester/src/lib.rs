@@ -12,7 +12,7 @@ use std::collections::HashMap;
 use std::fmt::Debug;
 use std::path::{Path, PathBuf};
 use std::thread;
-use portable_hash::{BuildPortableHasher, PortableHash};
+use portable_hash::{BuildPortableHasher, PortableHash, PortableSchema};
 use crate::fixture::{Fixture, FixtureState};
 
 pub use rng::*;
@@ -83,6 +83,9 @@ pub fn test_default_fixtures(fixtures: &mut FixtureDB<impl BuildPortableHasher>)
 ///     // Test your own PortableHash types that don't implement Debug.
 ///     fixtures.test_fixture_no_debug("test_other", "your custom object to be hashed");
 ///
+///     // Snapshot the hashing layout of your derived types.
+///     fixtures.test_schema::<MyType>("schema_my_type");
+///
 ///     // Log the summary stats and error if any hash outputs changed.
 ///     fixtures.finish();
 /// }
@@ -220,6 +223,34 @@ impl<H: BuildPortableHasher> FixtureDB<H> {
         }
     }
 
+    /// Snapshot the [`PortableSchema`] of a type.
+    ///
+    /// The schema string is hashed and stored like any other fixture, so any change to the
+    /// type's hashing layout fails the test, even if no other fixture covers the changed field or
+    /// variant. The current schema is logged for new and failed fixtures.
+    pub fn test_schema<T: PortableSchema + ?Sized>(&mut self, test_name: impl AsRef<str>) {
+        let test_name = test_name.as_ref();
+
+        // hash the schema using the provided hasher
+        let object_type = std::any::type_name::<T>();
+        let schema = T::PORTABLE_HASH_SCHEMA;
+        let actual_hash = self.hasher.hash_one(schema);
+
+        // store the hash in the fixture database
+        let fixture = self.log_hash_result(test_name, actual_hash);
+
+        // logging for the fixture state
+        match fixture.state() {
+            FixtureState::New => {
+                println!("new:  '{}' schema for {}: {}", test_name, object_type, schema);
+            }
+            FixtureState::Failed => {
+                println!("fail: '{}' schema changed for {}: {}", test_name, object_type, schema);
+            }
+            _ => {},  // we don't care about Passed or Skipped states in this context
+        }
+    }
+
     /// Returns a summary of the fixture states.
     ///
     /// True the test should fail to alert the user, with a summary message for all fixture states.
@@ -0,0 +1,166 @@
+//! A write-coalescing adapter for hashers with expensive `write` calls.
+
+use crate::{BuildPortableHasher, HashError, PathSegment, PortableHasher, PortableHasherOutput};
+
+/// A [`PortableHasher`] adapter that collects small writes in an `N`-byte stack buffer, and passes
+/// them to the wrapped hasher's [`write`](PortableHasher::write) in blocks.
+///
+/// Hashing a struct of many small fields makes one `write` call per field. For hashers with a
+/// high per-call cost, such as block-based cryptographic hashers, coalescing those calls is much
+/// faster. Writes that don't fit in the remaining buffer space flush the buffer first, and writes
+/// of at least `N` bytes are passed straight through after the flush.
+///
+/// Every `write_*` method goes through the default [`PortableHasher`] encodings and ends up in
+/// `write`, so the wrapped hasher only ever sees `write` calls. The output is therefore identical
+/// to the unbuffered hasher's only if the wrapped hasher:
+/// - Hashes the concatenation of the bytes passed to `write`, regardless of how they are split
+///   across calls, as streaming hashers such as SHA-256 do.
+/// - Doesn't override any other `write_*` method.
+///
+/// Trace hooks are forwarded to the wrapped hasher immediately, while the writes they surround
+/// may still be buffered, so tracing hashers shouldn't be wrapped.
+///
+/// [`finish`](PortableHasher::finish), [`finalize`](PortableHasherOutput::finalize), and
+/// [`check`](PortableHasher::check) take `&self`, so when the buffer isn't empty they clone the
+/// wrapped hasher to write the remaining bytes.
+///
+/// # Example
+/// ```ignore
+/// type BufferedSha256 = BufferedBuildHasher<Sha256BuildHasher, 64>;
+/// let digest: [u8; 32] = BufferedSha256::default().finalize_one(&record);
+/// ```
+#[derive(Clone, Debug)]
+pub struct BufferedHasher<H, const N: usize> {
+    hasher: H,
+    buffer: [u8; N],
+    len: usize,
+}
+
+impl<H, const N: usize> BufferedHasher<H, N> {
+    /// Wrap a hasher with an empty buffer.
+    pub fn new(hasher: H) -> Self {
+        Self {
+            hasher,
+            buffer: [0; N],
+            len: 0,
+        }
+    }
+}
+
+impl<H: PortableHasher, const N: usize> BufferedHasher<H, N> {
+    /// Write any buffered bytes to the wrapped hasher.
+    #[inline]
+    pub fn flush(&mut self) {
+        if self.len > 0 {
+            self.hasher.write(&self.buffer[..self.len]);
+            self.len = 0;
+        }
+    }
+
+    /// Flush the buffer and return the wrapped hasher.
+    pub fn into_inner(mut self) -> H {
+        self.flush();
+        self.hasher
+    }
+
+    /// A copy of the wrapped hasher with the buffered bytes written to it.
+    fn flushed(&self) -> H
+    where
+        H: Clone,
+    {
+        let mut hasher = self.hasher.clone();
+        hasher.write(&self.buffer[..self.len]);
+        hasher
+    }
+}
+
+impl<H: Default, const N: usize> Default for BufferedHasher<H, N> {
+    fn default() -> Self {
+        Self::new(H::default())
+    }
+}
+
+impl<H: PortableHasher + Clone, const N: usize> PortableHasher for BufferedHasher<H, N> {
+    #[inline]
+    fn finish(&self) -> u64 {
+        if self.len == 0 {
+            self.hasher.finish()
+        } else {
+            self.flushed().finish()
+        }
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        if bytes.len() <= N - self.len {
+            self.buffer[self.len..self.len + bytes.len()].copy_from_slice(bytes);
+            self.len += bytes.len();
+            return;
+        }
+
+        self.flush();
+        if bytes.len() < N {
+            self.buffer[..bytes.len()].copy_from_slice(bytes);
+            self.len = bytes.len();
+        } else {
+            self.hasher.write(bytes);
+        }
+    }
+
+    #[inline]
+    fn write_short<const LEN: usize>(&mut self, bytes: [u8; LEN]) {
+        self.write(&bytes);
+    }
+
+    const TRACING: bool = H::TRACING;
+
+    #[inline]
+    fn trace_enter(&mut self, segment: PathSegment<'_>) {
+        self.hasher.trace_enter(segment);
+    }
+
+    #[inline]
+    fn trace_exit(&mut self) {
+        self.hasher.trace_exit();
+    }
+
+    #[inline]
+    fn check(&self) -> Result<(), HashError> {
+        if self.len == 0 {
+            self.hasher.check()
+        } else {
+            self.flushed().check()
+        }
+    }
+}
+
+impl<H, O, const N: usize> PortableHasherOutput<O> for BufferedHasher<H, N>
+where
+    H: PortableHasherOutput<O> + Clone,
+{
+    #[inline]
+    fn finalize(&self) -> O {
+        if self.len == 0 {
+            self.hasher.finalize()
+        } else {
+            self.flushed().finalize()
+        }
+    }
+}
+
+/// A [`BuildPortableHasher`] for [`BufferedHasher`], wrapping each hasher built by `B`.
+#[derive(Clone, Debug, Default)]
+pub struct BufferedBuildHasher<B, const N: usize>(pub B);
+
+impl<B, const N: usize> BuildPortableHasher for BufferedBuildHasher<B, N>
+where
+    B: BuildPortableHasher,
+    B::PortableHasher: Clone,
+{
+    type PortableHasher = BufferedHasher<B::PortableHasher, N>;
+
+    #[inline]
+    fn build_hasher(&self) -> Self::PortableHasher {
+        BufferedHasher::new(self.0.build_hasher())
+    }
+}
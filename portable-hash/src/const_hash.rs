@@ -0,0 +1,204 @@
+//! Compile-time portable hashing, for static lookup tables keyed by hashes of literals.
+//!
+//! [`FnvHasher`] is an FNV-1a 64-bit hasher whose `const fn` methods write values with the same
+//! encodings as the default [`PortableHasher`] methods. A hash computed in a `const` therefore
+//! matches the hash of the same value computed at runtime, through `FnvHasher`'s
+//! `PortableHasher` impl:
+//!
+//! ```
+//! use portable_hash::BuildPortableHasher;
+//! use portable_hash::const_hash::{self, FnvBuildHasher, FnvHasher};
+//!
+//! const USER: u64 = const_hash::hash_str("user");
+//! const USER_42: u64 = FnvHasher::new().str("user").u32(42).finish();
+//!
+//! let build = FnvBuildHasher::default();
+//! assert_eq!(USER, build.hash_one("user"));
+//! assert_eq!(USER_42, build.hash_one(("user", 42u32)));
+//! ```
+//!
+//! Tuples and structs are hashed as their fields in order, so they're written by chaining one
+//! method per field. FNV-1a is fast and simple, but isn't resistant to collisions crafted by an
+//! attacker, so it shouldn't be used for keys from untrusted input.
+
+use crate::{DefaultBuildPortableHasher, PortableHasher, PortableHasherOutput};
+
+/// The FNV-1a 64-bit offset basis.
+const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+
+/// The FNV-1a 64-bit prime.
+const PRIME: u64 = 0x00000100000001B3;
+
+/// An FNV-1a 64-bit [`PortableHasher`], with `const fn` methods for hashing at compile time.
+///
+/// The `const fn` methods take and return the hasher by value, so that they can be chained in a
+/// `const` on Rust versions without `&mut` in const contexts. Each writes its value exactly as the
+/// `PortableHasher` method of the matching name does by default:
+/// - [`raw`](Self::raw) writes bytes as they are, like [`write`](PortableHasher::write).
+/// - [`u8`](Self::u8) through [`i128`](Self::i128) write integers as little-endian bytes, and
+///   [`usize`](Self::usize) and [`isize`](Self::isize) widen to 64 bits first.
+/// - [`len_prefix`](Self::len_prefix) writes a length as a `u64`.
+/// - [`str`](Self::str) and [`bytes`](Self::bytes) write a length prefix and then the bytes, as
+///   `str`, `&[u8]`, and `[u8; N]` values are hashed.
+///
+/// The `PortableHasher` impl uses the default methods, so the bytes it hashes are the canonical
+/// encoding of the value.
+#[derive(Clone, Copy, Debug)]
+pub struct FnvHasher {
+    state: u64,
+}
+
+impl FnvHasher {
+    /// Create a hasher with the FNV-1a offset basis as its state.
+    pub const fn new() -> Self {
+        Self { state: OFFSET_BASIS }
+    }
+
+    /// The hash of the bytes written so far.
+    pub const fn finish(&self) -> u64 {
+        self.state
+    }
+
+    /// Write bytes as they are, like [`PortableHasher::write`].
+    #[must_use]
+    pub const fn raw(self, bytes: &[u8]) -> Self {
+        let mut state = self.state;
+        let mut i = 0;
+        while i < bytes.len() {
+            state ^= bytes[i] as u64;
+            state = state.wrapping_mul(PRIME);
+            i += 1;
+        }
+        Self { state }
+    }
+
+    /// Write a `u8`, like [`PortableHasher::write_u8`].
+    #[must_use]
+    pub const fn u8(self, i: u8) -> Self {
+        self.raw(&i.to_le_bytes())
+    }
+
+    /// Write a `u16`, like [`PortableHasher::write_u16`].
+    #[must_use]
+    pub const fn u16(self, i: u16) -> Self {
+        self.raw(&i.to_le_bytes())
+    }
+
+    /// Write a `u32`, like [`PortableHasher::write_u32`].
+    #[must_use]
+    pub const fn u32(self, i: u32) -> Self {
+        self.raw(&i.to_le_bytes())
+    }
+
+    /// Write a `u64`, like [`PortableHasher::write_u64`].
+    #[must_use]
+    pub const fn u64(self, i: u64) -> Self {
+        self.raw(&i.to_le_bytes())
+    }
+
+    /// Write a `u128`, like [`PortableHasher::write_u128`].
+    #[must_use]
+    pub const fn u128(self, i: u128) -> Self {
+        self.raw(&i.to_le_bytes())
+    }
+
+    /// Write a `usize` widened to a `u64`, like [`PortableHasher::write_usize`].
+    #[must_use]
+    pub const fn usize(self, i: usize) -> Self {
+        self.u64(i as u64)
+    }
+
+    /// Write an `i8`, like [`PortableHasher::write_i8`].
+    #[must_use]
+    pub const fn i8(self, i: i8) -> Self {
+        self.raw(&i.to_le_bytes())
+    }
+
+    /// Write an `i16`, like [`PortableHasher::write_i16`].
+    #[must_use]
+    pub const fn i16(self, i: i16) -> Self {
+        self.raw(&i.to_le_bytes())
+    }
+
+    /// Write an `i32`, like [`PortableHasher::write_i32`].
+    #[must_use]
+    pub const fn i32(self, i: i32) -> Self {
+        self.raw(&i.to_le_bytes())
+    }
+
+    /// Write an `i64`, like [`PortableHasher::write_i64`].
+    #[must_use]
+    pub const fn i64(self, i: i64) -> Self {
+        self.raw(&i.to_le_bytes())
+    }
+
+    /// Write an `i128`, like [`PortableHasher::write_i128`].
+    #[must_use]
+    pub const fn i128(self, i: i128) -> Self {
+        self.raw(&i.to_le_bytes())
+    }
+
+    /// Write an `isize` widened to an `i64`, like [`PortableHasher::write_isize`].
+    #[must_use]
+    pub const fn isize(self, i: isize) -> Self {
+        self.i64(i as i64)
+    }
+
+    /// Write a length, like [`PortableHasher::write_len_prefix`].
+    #[must_use]
+    pub const fn len_prefix(self, len: usize) -> Self {
+        self.usize(len)
+    }
+
+    /// Write a string with its length prefix, like [`PortableHasher::write_str`].
+    #[must_use]
+    pub const fn str(self, s: &str) -> Self {
+        self.bytes(s.as_bytes())
+    }
+
+    /// Write a byte slice with its length prefix, like [`PortableHasher::write_bytes`].
+    #[must_use]
+    pub const fn bytes(self, bytes: &[u8]) -> Self {
+        self.len_prefix(bytes.len()).raw(bytes)
+    }
+}
+
+impl Default for FnvHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PortableHasher for FnvHasher {
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.state
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        *self = self.raw(bytes);
+    }
+}
+
+impl PortableHasherOutput<u64> for FnvHasher {
+    #[inline]
+    fn finalize(&self) -> u64 {
+        self.state
+    }
+}
+
+/// A [`BuildPortableHasher`](crate::BuildPortableHasher) for [`FnvHasher`].
+pub type FnvBuildHasher = DefaultBuildPortableHasher<FnvHasher>;
+
+/// Hash a string at compile time, matching the runtime hash of a `str` or `String` by
+/// [`FnvHasher`].
+pub const fn hash_str(s: &str) -> u64 {
+    FnvHasher::new().str(s).finish()
+}
+
+/// Hash a byte slice or array at compile time, matching the runtime hash of a `[u8]`, `[u8; N]`,
+/// or `Vec<u8>` by [`FnvHasher`].
+pub const fn hash_bytes(bytes: &[u8]) -> u64 {
+    FnvHasher::new().bytes(bytes).finish()
+}
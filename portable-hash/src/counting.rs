@@ -0,0 +1,239 @@
+//! A hasher that counts what is written to it, for estimating hashing cost and encoded sizes.
+
+use crate::{HashError, PathSegment, PortableHasher, PortableHasherOutput};
+
+/// The number of bytes written to a [`CountingHasher`], and the number of calls to each of its
+/// `write_*` methods.
+///
+/// Only the calls made on the `CountingHasher` itself are counted. Calls that the default method
+/// implementations make internally aren't: a `write_str` call counts once towards
+/// [`write_str`](Self::write_str), and not towards `write_len_prefix`, `write_usize`, or `write`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct HashStats {
+    /// The number of bytes written, using the default [`PortableHasher`] encodings: the length of
+    /// the canonical encoding of the hashed values.
+    pub bytes: u64,
+    /// Calls to [`write`](PortableHasher::write).
+    pub write: u64,
+    /// Calls to [`write_u8`](PortableHasher::write_u8).
+    pub write_u8: u64,
+    /// Calls to [`write_u16`](PortableHasher::write_u16).
+    pub write_u16: u64,
+    /// Calls to [`write_u32`](PortableHasher::write_u32).
+    pub write_u32: u64,
+    /// Calls to [`write_u64`](PortableHasher::write_u64).
+    pub write_u64: u64,
+    /// Calls to [`write_u128`](PortableHasher::write_u128).
+    pub write_u128: u64,
+    /// Calls to [`write_usize`](PortableHasher::write_usize).
+    pub write_usize: u64,
+    /// Calls to [`write_i8`](PortableHasher::write_i8).
+    pub write_i8: u64,
+    /// Calls to [`write_i16`](PortableHasher::write_i16).
+    pub write_i16: u64,
+    /// Calls to [`write_i32`](PortableHasher::write_i32).
+    pub write_i32: u64,
+    /// Calls to [`write_i64`](PortableHasher::write_i64).
+    pub write_i64: u64,
+    /// Calls to [`write_i128`](PortableHasher::write_i128).
+    pub write_i128: u64,
+    /// Calls to [`write_isize`](PortableHasher::write_isize).
+    pub write_isize: u64,
+    /// Calls to [`write_len_prefix`](PortableHasher::write_len_prefix).
+    pub write_len_prefix: u64,
+    /// Calls to [`write_str`](PortableHasher::write_str).
+    pub write_str: u64,
+    /// Calls to [`write_bytes`](PortableHasher::write_bytes).
+    pub write_bytes: u64,
+    /// Calls to [`write_short`](PortableHasher::write_short).
+    pub write_short: u64,
+}
+
+impl HashStats {
+    /// The total number of `write_*` calls.
+    pub fn calls(&self) -> u64 {
+        self.write
+            + self.write_u8
+            + self.write_u16
+            + self.write_u32
+            + self.write_u64
+            + self.write_u128
+            + self.write_usize
+            + self.write_i8
+            + self.write_i16
+            + self.write_i32
+            + self.write_i64
+            + self.write_i128
+            + self.write_isize
+            + self.write_len_prefix
+            + self.write_str
+            + self.write_bytes
+            + self.write_short
+    }
+}
+
+/// A [`PortableHasher`] that ignores every write. [`finish`](PortableHasher::finish) always
+/// returns 0.
+///
+/// This is the hasher wrapped by a standalone [`CountingHasher`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NullHasher;
+
+impl PortableHasher for NullHasher {
+    #[inline]
+    fn finish(&self) -> u64 {
+        0
+    }
+
+    #[inline]
+    fn write(&mut self, _bytes: &[u8]) {}
+}
+
+/// A [`PortableHasher`] that counts the bytes written to it and the calls to each `write_*`
+/// method, collected in [`HashStats`].
+///
+/// A standalone `CountingHasher`, created with [`CountingHasher::new`], doesn't hash anything. To
+/// count the writes made while hashing, wrap another hasher with [`CountingHasher::wrap`]: every
+/// call is forwarded to it unchanged, and [`finish`](PortableHasher::finish) returns its `finish`.
+/// [`finalize`](PortableHasherOutput::finalize) always returns the stats; use
+/// [`into_inner`](CountingHasher::into_inner), or a [`TeeHasher`](crate::TeeHasher) pairing a
+/// standalone `CountingHasher` with another hasher, to get both outputs.
+///
+/// # Example
+/// ```
+/// use portable_hash::{CountingHasher, PortableHash, PortableHasherOutput};
+///
+/// let mut counter = CountingHasher::new();
+/// (7u32, "name").portable_hash(&mut counter);
+///
+/// let stats = counter.finalize();
+/// assert_eq!(stats.write_u32, 1);
+/// assert_eq!(stats.write_str, 1);
+/// // 4 bytes for the `u32`, plus an 8-byte length prefix and 4 bytes for the string.
+/// assert_eq!(stats.bytes, 16);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct CountingHasher<H = NullHasher> {
+    hasher: H,
+    stats: HashStats,
+}
+
+impl CountingHasher {
+    /// Create a standalone `CountingHasher`, which counts writes without hashing them.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<H> CountingHasher<H> {
+    /// Wrap a hasher, counting the writes forwarded to it.
+    pub fn wrap(hasher: H) -> Self {
+        Self {
+            hasher,
+            stats: HashStats::default(),
+        }
+    }
+
+    /// The stats counted so far.
+    pub fn stats(&self) -> &HashStats {
+        &self.stats
+    }
+
+    /// A reference to the wrapped hasher.
+    pub fn get_ref(&self) -> &H {
+        &self.hasher
+    }
+
+    /// Consume the `CountingHasher`, returning the wrapped hasher.
+    pub fn into_inner(self) -> H {
+        self.hasher
+    }
+}
+
+macro_rules! count_int_writes {
+    ($($method:ident: $ty:ty = $size:expr),* $(,)?) => {
+        $(
+            #[inline]
+            fn $method(&mut self, i: $ty) {
+                self.stats.$method += 1;
+                self.stats.bytes += $size;
+                self.hasher.$method(i);
+            }
+        )*
+    };
+}
+
+impl<H: PortableHasher> PortableHasher for CountingHasher<H> {
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.hasher.finish()
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        self.stats.write += 1;
+        self.stats.bytes += bytes.len() as u64;
+        self.hasher.write(bytes);
+    }
+
+    count_int_writes!(
+        write_u8: u8 = 1,
+        write_u16: u16 = 2,
+        write_u32: u32 = 4,
+        write_u64: u64 = 8,
+        write_u128: u128 = 16,
+        write_usize: usize = 8,
+        write_i8: i8 = 1,
+        write_i16: i16 = 2,
+        write_i32: i32 = 4,
+        write_i64: i64 = 8,
+        write_i128: i128 = 16,
+        write_isize: isize = 8,
+        write_len_prefix: usize = 8,
+    );
+
+    #[inline]
+    fn write_str(&mut self, s: &str) {
+        self.stats.write_str += 1;
+        self.stats.bytes += 8 + s.len() as u64;
+        self.hasher.write_str(s);
+    }
+
+    #[inline]
+    fn write_bytes(&mut self, bytes: &[u8]) {
+        self.stats.write_bytes += 1;
+        self.stats.bytes += 8 + bytes.len() as u64;
+        self.hasher.write_bytes(bytes);
+    }
+
+    #[inline]
+    fn write_short<const LEN: usize>(&mut self, bytes: [u8; LEN]) {
+        self.stats.write_short += 1;
+        self.stats.bytes += LEN as u64;
+        self.hasher.write_short(bytes);
+    }
+
+    const TRACING: bool = H::TRACING;
+
+    #[inline]
+    fn trace_enter(&mut self, segment: PathSegment<'_>) {
+        self.hasher.trace_enter(segment);
+    }
+
+    #[inline]
+    fn trace_exit(&mut self) {
+        self.hasher.trace_exit();
+    }
+
+    #[inline]
+    fn check(&self) -> Result<(), HashError> {
+        self.hasher.check()
+    }
+}
+
+impl<H: PortableHasher> PortableHasherOutput<HashStats> for CountingHasher<H> {
+    #[inline]
+    fn finalize(&self) -> HashStats {
+        self.stats
+    }
+}
@@ -0,0 +1,617 @@
+//! Object-safe companions to [`PortableHash`] and [`PortableHasher`], for hashing trait objects.
+
+use crate::{HashError, PathSegment, PortableHash, PortableHasher};
+
+/// An object-safe version of [`PortableHasher`], implemented for every `PortableHasher`.
+///
+/// `PortableHasher` can't be used as a trait object, because
+/// [`write_short`](PortableHasher::write_short) is generic over the array length. Every method
+/// here forwards to the `PortableHasher` method of the same name, so hashers that override
+/// methods such as `write_str` or `write_bytes` produce the same output through
+/// `dyn DynPortableHasher` as they do directly.
+///
+/// `dyn DynPortableHasher` implements `PortableHasher` itself, forwarding back to these methods.
+/// The methods are prefixed with `dyn_` so that they don't clash with the `PortableHasher`
+/// methods when both traits are in scope.
+///
+/// The trace hooks are forwarded too, so tracing hashers and the depth limit of
+/// [`LimitedHasher`](crate::LimitedHasher) see through trait objects. Whether the hasher behind a
+/// trait object is tracing isn't known at compile time, so `dyn DynPortableHasher` sets
+/// [`TRACING`](PortableHasher::TRACING) to `true`; [`dyn_tracing`](Self::dyn_tracing) reports the
+/// wrapped hasher's value.
+///
+/// To choose the hashing algorithm at runtime, box a builder with `BoxedBuildPortableHasher` and
+/// look it up by name in a `HasherRegistry` (both require the `alloc` feature).
+pub trait DynPortableHasher {
+    /// Forwards to [`PortableHasher::finish`].
+    fn dyn_finish(&self) -> u64;
+    /// Forwards to [`PortableHasher::write`].
+    fn dyn_write(&mut self, bytes: &[u8]);
+    /// Forwards to [`PortableHasher::write_u8`].
+    fn dyn_write_u8(&mut self, i: u8);
+    /// Forwards to [`PortableHasher::write_u16`].
+    fn dyn_write_u16(&mut self, i: u16);
+    /// Forwards to [`PortableHasher::write_u32`].
+    fn dyn_write_u32(&mut self, i: u32);
+    /// Forwards to [`PortableHasher::write_u64`].
+    fn dyn_write_u64(&mut self, i: u64);
+    /// Forwards to [`PortableHasher::write_u128`].
+    fn dyn_write_u128(&mut self, i: u128);
+    /// Forwards to [`PortableHasher::write_usize`].
+    fn dyn_write_usize(&mut self, i: usize);
+    /// Forwards to [`PortableHasher::write_i8`].
+    fn dyn_write_i8(&mut self, i: i8);
+    /// Forwards to [`PortableHasher::write_i16`].
+    fn dyn_write_i16(&mut self, i: i16);
+    /// Forwards to [`PortableHasher::write_i32`].
+    fn dyn_write_i32(&mut self, i: i32);
+    /// Forwards to [`PortableHasher::write_i64`].
+    fn dyn_write_i64(&mut self, i: i64);
+    /// Forwards to [`PortableHasher::write_i128`].
+    fn dyn_write_i128(&mut self, i: i128);
+    /// Forwards to [`PortableHasher::write_isize`].
+    fn dyn_write_isize(&mut self, i: isize);
+    /// Forwards to [`PortableHasher::write_len_prefix`].
+    fn dyn_write_len_prefix(&mut self, len: usize);
+    /// Forwards to [`PortableHasher::write_str`].
+    fn dyn_write_str(&mut self, s: &str);
+    /// Forwards to [`PortableHasher::write_bytes`].
+    fn dyn_write_bytes(&mut self, bytes: &[u8]);
+    /// Forwards to [`PortableHasher::write_short`] with `LEN` set to the length of `bytes`.
+    ///
+    /// Lengths of up to 16 bytes, which covers every integer width, keep their `write_short`
+    /// call. Longer arrays are passed to [`PortableHasher::write`] instead, which is what the
+    /// default `write_short` does.
+    fn dyn_write_short(&mut self, bytes: &[u8]);
+    /// Returns the wrapped hasher's [`PortableHasher::TRACING`].
+    fn dyn_tracing(&self) -> bool;
+    /// Forwards to [`PortableHasher::trace_enter`].
+    fn dyn_trace_enter(&mut self, segment: PathSegment<'_>);
+    /// Forwards to [`PortableHasher::trace_exit`].
+    fn dyn_trace_exit(&mut self);
+    /// Forwards to [`PortableHasher::check`].
+    fn dyn_check(&self) -> Result<(), HashError>;
+}
+
+/// Call `write_short` with the array length matching `bytes`, for each of the listed lengths.
+macro_rules! write_short_dispatch {
+    ($state:ident, $bytes:ident, $($len:literal),*) => {
+        match $bytes.len() {
+            $(
+                $len => {
+                    let mut array = [0u8; $len];
+                    array.copy_from_slice($bytes);
+                    $state.write_short(array);
+                }
+            )*
+            _ => $state.write($bytes),
+        }
+    };
+}
+
+impl<H: PortableHasher> DynPortableHasher for H {
+    #[inline]
+    fn dyn_finish(&self) -> u64 {
+        self.finish()
+    }
+
+    #[inline]
+    fn dyn_write(&mut self, bytes: &[u8]) {
+        self.write(bytes);
+    }
+
+    #[inline]
+    fn dyn_write_u8(&mut self, i: u8) {
+        self.write_u8(i);
+    }
+
+    #[inline]
+    fn dyn_write_u16(&mut self, i: u16) {
+        self.write_u16(i);
+    }
+
+    #[inline]
+    fn dyn_write_u32(&mut self, i: u32) {
+        self.write_u32(i);
+    }
+
+    #[inline]
+    fn dyn_write_u64(&mut self, i: u64) {
+        self.write_u64(i);
+    }
+
+    #[inline]
+    fn dyn_write_u128(&mut self, i: u128) {
+        self.write_u128(i);
+    }
+
+    #[inline]
+    fn dyn_write_usize(&mut self, i: usize) {
+        self.write_usize(i);
+    }
+
+    #[inline]
+    fn dyn_write_i8(&mut self, i: i8) {
+        self.write_i8(i);
+    }
+
+    #[inline]
+    fn dyn_write_i16(&mut self, i: i16) {
+        self.write_i16(i);
+    }
+
+    #[inline]
+    fn dyn_write_i32(&mut self, i: i32) {
+        self.write_i32(i);
+    }
+
+    #[inline]
+    fn dyn_write_i64(&mut self, i: i64) {
+        self.write_i64(i);
+    }
+
+    #[inline]
+    fn dyn_write_i128(&mut self, i: i128) {
+        self.write_i128(i);
+    }
+
+    #[inline]
+    fn dyn_write_isize(&mut self, i: isize) {
+        self.write_isize(i);
+    }
+
+    #[inline]
+    fn dyn_write_len_prefix(&mut self, len: usize) {
+        self.write_len_prefix(len);
+    }
+
+    #[inline]
+    fn dyn_write_str(&mut self, s: &str) {
+        self.write_str(s);
+    }
+
+    #[inline]
+    fn dyn_write_bytes(&mut self, bytes: &[u8]) {
+        self.write_bytes(bytes);
+    }
+
+    #[inline]
+    fn dyn_write_short(&mut self, bytes: &[u8]) {
+        write_short_dispatch!(self, bytes, 0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16);
+    }
+
+    #[inline]
+    fn dyn_tracing(&self) -> bool {
+        H::TRACING
+    }
+
+    #[inline]
+    fn dyn_trace_enter(&mut self, segment: PathSegment<'_>) {
+        self.trace_enter(segment);
+    }
+
+    #[inline]
+    fn dyn_trace_exit(&mut self) {
+        self.trace_exit();
+    }
+
+    #[inline]
+    fn dyn_check(&self) -> Result<(), HashError> {
+        self.check()
+    }
+}
+
+/// Implement every `PortableHasher` method other than `finish` and `check` by calling the matching
+/// `DynPortableHasher` method on `self`, or on `self` followed by the given field accesses and
+/// method calls. `TRACING` is `true`, as the wrapped hasher's value is only known at runtime.
+macro_rules! forward_writes_to_dyn {
+    ($($access:tt)*) => {
+        #[inline]
+        fn write(&mut self, bytes: &[u8]) {
+            self$($access)*.dyn_write(bytes);
+        }
+
+        #[inline]
+        fn write_u8(&mut self, i: u8) {
+            self$($access)*.dyn_write_u8(i);
+        }
+
+        #[inline]
+        fn write_u16(&mut self, i: u16) {
+            self$($access)*.dyn_write_u16(i);
+        }
+
+        #[inline]
+        fn write_u32(&mut self, i: u32) {
+            self$($access)*.dyn_write_u32(i);
+        }
+
+        #[inline]
+        fn write_u64(&mut self, i: u64) {
+            self$($access)*.dyn_write_u64(i);
+        }
+
+        #[inline]
+        fn write_u128(&mut self, i: u128) {
+            self$($access)*.dyn_write_u128(i);
+        }
+
+        #[inline]
+        fn write_usize(&mut self, i: usize) {
+            self$($access)*.dyn_write_usize(i);
+        }
+
+        #[inline]
+        fn write_i8(&mut self, i: i8) {
+            self$($access)*.dyn_write_i8(i);
+        }
+
+        #[inline]
+        fn write_i16(&mut self, i: i16) {
+            self$($access)*.dyn_write_i16(i);
+        }
+
+        #[inline]
+        fn write_i32(&mut self, i: i32) {
+            self$($access)*.dyn_write_i32(i);
+        }
+
+        #[inline]
+        fn write_i64(&mut self, i: i64) {
+            self$($access)*.dyn_write_i64(i);
+        }
+
+        #[inline]
+        fn write_i128(&mut self, i: i128) {
+            self$($access)*.dyn_write_i128(i);
+        }
+
+        #[inline]
+        fn write_isize(&mut self, i: isize) {
+            self$($access)*.dyn_write_isize(i);
+        }
+
+        #[inline]
+        fn write_len_prefix(&mut self, len: usize) {
+            self$($access)*.dyn_write_len_prefix(len);
+        }
+
+        #[inline]
+        fn write_str(&mut self, s: &str) {
+            self$($access)*.dyn_write_str(s);
+        }
+
+        #[inline]
+        fn write_bytes(&mut self, bytes: &[u8]) {
+            self$($access)*.dyn_write_bytes(bytes);
+        }
+
+        #[inline]
+        fn write_short<const LEN: usize>(&mut self, bytes: [u8; LEN]) {
+            self$($access)*.dyn_write_short(&bytes);
+        }
+
+        const TRACING: bool = true;
+
+        #[inline]
+        fn trace_enter(&mut self, segment: PathSegment<'_>) {
+            self$($access)*.dyn_trace_enter(segment);
+        }
+
+        #[inline]
+        fn trace_exit(&mut self) {
+            self$($access)*.dyn_trace_exit();
+        }
+    };
+}
+
+impl PortableHasher for dyn DynPortableHasher + '_ {
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.dyn_finish()
+    }
+
+    #[inline]
+    fn check(&self) -> Result<(), HashError> {
+        self.dyn_check()
+    }
+
+    forward_writes_to_dyn!();
+}
+
+/// An object-safe version of [`PortableHash`], implemented for every `PortableHash` type.
+///
+/// `PortableHash` can't be used as a trait object, because
+/// [`portable_hash`](PortableHash::portable_hash) is generic over the hasher. Use
+/// `dyn DynPortableHash` instead, which implements `PortableHash`, to hash heterogeneous values
+/// such as `Vec<Box<dyn DynPortableHash>>`. A value hashes identically through
+/// `dyn DynPortableHash` and directly.
+///
+/// Add `DynPortableHash` as a supertrait to make your own trait objects hashable.
+///
+/// # Example
+/// ```
+/// use portable_hash::{BuildPortableHasher, DynPortableHash, PortableHash, PortableHasher};
+/// # use portable_hash::DefaultBuildPortableHasher;
+/// # #[derive(Default)]
+/// # struct Fnv(u64);
+/// # impl PortableHasher for Fnv {
+/// #     fn finish(&self) -> u64 { self.0 }
+/// #     fn write(&mut self, bytes: &[u8]) {
+/// #         for byte in bytes { self.0 = (self.0 ^ u64::from(*byte)).wrapping_mul(0x100000001b3); }
+/// #     }
+/// # }
+/// # let build = DefaultBuildPortableHasher::<Fnv>::default();
+///
+/// trait Plugin: DynPortableHash {
+///     fn name(&self) -> &str;
+/// }
+///
+/// impl PortableHash for dyn Plugin {
+///     fn portable_hash<H: PortableHasher>(&self, state: &mut H) {
+///         self.dyn_portable_hash(state);
+///     }
+/// }
+///
+/// #[derive(PortableHash)]
+/// struct Resize {
+///     width: u32,
+///     height: u32,
+/// }
+///
+/// impl Plugin for Resize {
+///     fn name(&self) -> &str {
+///         "resize"
+///     }
+/// }
+///
+/// let values: [&dyn DynPortableHash; 2] = [&Resize { width: 64, height: 48 }, &"blur"];
+/// assert_eq!(build.hash_one(values[0]), build.hash_one(Resize { width: 64, height: 48 }));
+/// assert_eq!(build.hash_one(values[1]), build.hash_one("blur"));
+///
+/// let plugins: [&dyn Plugin; 1] = [&Resize { width: 64, height: 48 }];
+/// assert_eq!(build.hash_one(plugins), build.hash_one([Resize { width: 64, height: 48 }]));
+/// ```
+pub trait DynPortableHash {
+    /// Hash the value using the specified hasher, exactly as
+    /// [`portable_hash`](PortableHash::portable_hash) would.
+    fn dyn_portable_hash(&self, state: &mut dyn DynPortableHasher);
+}
+
+impl<T: PortableHash> DynPortableHash for T {
+    #[inline]
+    fn dyn_portable_hash(&self, state: &mut dyn DynPortableHasher) {
+        // `portable_hash` needs a sized hasher, which `&mut dyn DynPortableHasher` is.
+        self.portable_hash(&mut &mut *state);
+    }
+}
+
+macro_rules! impl_portable_hash_for_dyn {
+    ($($ty:ty),*) => {
+        $(
+            impl PortableHash for $ty {
+                #[inline]
+                fn portable_hash<H: PortableHasher>(&self, state: &mut H) {
+                    self.dyn_portable_hash(state);
+                }
+            }
+        )*
+    };
+}
+
+impl_portable_hash_for_dyn!(
+    dyn DynPortableHash + '_,
+    dyn DynPortableHash + Send + '_,
+    dyn DynPortableHash + Send + Sync + '_
+);
+
+#[cfg(feature = "alloc")]
+pub use self::boxed::*;
+
+#[cfg(feature = "alloc")]
+mod boxed {
+    extern crate alloc;
+    use alloc::{boxed::Box, collections::BTreeMap, string::String, vec::Vec};
+
+    use super::DynPortableHasher;
+    use crate::{BuildPortableHasher, HashError, PathSegment, PortableHasher, PortableHasherOutput};
+
+    /// A hasher paired with the function that renders its output as bytes.
+    struct OutputHasher<H> {
+        hasher: H,
+        output: fn(&H) -> Vec<u8>,
+    }
+
+    /// An object-safe hasher that can also render its output as bytes.
+    trait ErasedHasher: Send {
+        fn hasher(&self) -> &dyn DynPortableHasher;
+        fn hasher_mut(&mut self) -> &mut dyn DynPortableHasher;
+        fn finalize_bytes(&self) -> Vec<u8>;
+    }
+
+    impl<H: PortableHasher + Send> ErasedHasher for OutputHasher<H> {
+        fn hasher(&self) -> &dyn DynPortableHasher {
+            &self.hasher
+        }
+
+        fn hasher_mut(&mut self) -> &mut dyn DynPortableHasher {
+            &mut self.hasher
+        }
+
+        fn finalize_bytes(&self) -> Vec<u8> {
+            (self.output)(&self.hasher)
+        }
+    }
+
+    /// An object-safe builder of [`ErasedHasher`]s.
+    trait ErasedBuild: Send + Sync {
+        fn build(&self) -> Box<dyn ErasedHasher>;
+    }
+
+    struct OutputBuild<B: BuildPortableHasher> {
+        builder: B,
+        output: fn(&B::PortableHasher) -> Vec<u8>,
+    }
+
+    impl<B> ErasedBuild for OutputBuild<B>
+    where
+        B: BuildPortableHasher + Send + Sync,
+        B::PortableHasher: Send + 'static,
+    {
+        fn build(&self) -> Box<dyn ErasedHasher> {
+            Box::new(OutputHasher {
+                hasher: self.builder.build_hasher(),
+                output: self.output,
+            })
+        }
+    }
+
+    /// A [`BuildPortableHasher`] whose hashing algorithm is chosen at runtime, usually through a
+    /// [`HasherRegistry`].
+    ///
+    /// The hashers it builds are [`BoxedPortableHasher`]s, which forward every call to the
+    /// wrapped algorithm through [`DynPortableHasher`], so they produce the same output as the
+    /// wrapped builder's own hashers. The output is available as a `u64` through
+    /// [`hash_one`](BuildPortableHasher::hash_one), or as bytes through
+    /// [`finalize_one`](BuildPortableHasher::finalize_one) with `Vec<u8>` as the output type.
+    pub struct BoxedBuildPortableHasher {
+        builder: Box<dyn ErasedBuild>,
+    }
+
+    impl BoxedBuildPortableHasher {
+        /// Box a builder whose byte output is the little-endian bytes of
+        /// [`finish`](PortableHasher::finish).
+        pub fn new<B>(builder: B) -> Self
+        where
+            B: BuildPortableHasher + Send + Sync + 'static,
+            B::PortableHasher: Send + 'static,
+        {
+            Self {
+                builder: Box::new(OutputBuild {
+                    builder,
+                    output: |hasher: &B::PortableHasher| hasher.finish().to_le_bytes().to_vec(),
+                }),
+            }
+        }
+
+        /// Box a builder whose byte output is its [`PortableHasherOutput`] of type `O`, such as
+        /// `[u8; 32]` for a SHA-256 hasher.
+        pub fn with_output<B, O>(builder: B) -> Self
+        where
+            B: BuildPortableHasher + Send + Sync + 'static,
+            B::PortableHasher: PortableHasherOutput<O> + Send + 'static,
+            O: AsRef<[u8]>,
+        {
+            Self {
+                builder: Box::new(OutputBuild {
+                    builder,
+                    output: |hasher: &B::PortableHasher| hasher.finalize().as_ref().to_vec(),
+                }),
+            }
+        }
+    }
+
+    impl BuildPortableHasher for BoxedBuildPortableHasher {
+        type PortableHasher = BoxedPortableHasher;
+
+        fn build_hasher(&self) -> Self::PortableHasher {
+            BoxedPortableHasher {
+                hasher: self.builder.build(),
+            }
+        }
+    }
+
+    /// A hasher built by a [`BoxedBuildPortableHasher`], forwarding to a hasher chosen at
+    /// runtime.
+    pub struct BoxedPortableHasher {
+        hasher: Box<dyn ErasedHasher>,
+    }
+
+    impl PortableHasher for BoxedPortableHasher {
+        #[inline]
+        fn finish(&self) -> u64 {
+            self.hasher.hasher().dyn_finish()
+        }
+
+        #[inline]
+        fn check(&self) -> Result<(), HashError> {
+            self.hasher.hasher().dyn_check()
+        }
+
+        forward_writes_to_dyn!(.hasher.hasher_mut());
+    }
+
+    impl PortableHasherOutput<Vec<u8>> for BoxedPortableHasher {
+        fn finalize(&self) -> Vec<u8> {
+            self.hasher.finalize_bytes()
+        }
+    }
+
+    /// A map from algorithm names to hasher builders, for choosing the hashing algorithm at
+    /// runtime, such as from a config file.
+    ///
+    /// # Example
+    /// ```
+    /// use portable_hash::{BoxedBuildPortableHasher, BuildPortableHasher, HasherRegistry};
+    /// # use portable_hash::{DefaultBuildPortableHasher, PortableHasher, PortableHasherOutput};
+    /// # #[derive(Default)]
+    /// # struct Fnv(u64);
+    /// # impl PortableHasher for Fnv {
+    /// #     fn finish(&self) -> u64 { self.0 ^ 0xcbf29ce484222325 }
+    /// #     fn write(&mut self, bytes: &[u8]) {
+    /// #         for byte in bytes { self.0 = (self.0 ^ u64::from(*byte)).wrapping_mul(0x100000001b3); }
+    /// #     }
+    /// # }
+    /// # impl PortableHasherOutput<[u8; 8]> for Fnv {
+    /// #     fn finalize(&self) -> [u8; 8] { self.finish().to_be_bytes() }
+    /// # }
+    /// # type FnvBuildHasher = DefaultBuildPortableHasher<Fnv>;
+    ///
+    /// let mut registry = HasherRegistry::new();
+    /// registry.register("fnv", BoxedBuildPortableHasher::with_output::<_, [u8; 8]>(FnvBuildHasher::default()));
+    ///
+    /// let algorithm = "fnv"; // read from config
+    /// let builder = registry.get(algorithm).expect("unknown hash algorithm");
+    /// let digest: Vec<u8> = builder.finalize_one(("order", 42u64));
+    ///
+    /// let expected: [u8; 8] = FnvBuildHasher::default().finalize_one(("order", 42u64));
+    /// assert_eq!(digest, expected);
+    /// assert_eq!(builder.hash_one(7u32), FnvBuildHasher::default().hash_one(7u32));
+    /// ```
+    #[derive(Default)]
+    pub struct HasherRegistry {
+        builders: BTreeMap<String, BoxedBuildPortableHasher>,
+    }
+
+    impl HasherRegistry {
+        /// Create an empty registry.
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Register a builder under `name`, returning the builder previously registered under
+        /// that name, if any.
+        pub fn register(
+            &mut self,
+            name: impl Into<String>,
+            builder: BoxedBuildPortableHasher,
+        ) -> Option<BoxedBuildPortableHasher> {
+            self.builders.insert(name.into(), builder)
+        }
+
+        /// The builder registered under `name`.
+        pub fn get(&self, name: &str) -> Option<&BoxedBuildPortableHasher> {
+            self.builders.get(name)
+        }
+
+        /// Remove the builder registered under `name`, returning it.
+        pub fn remove(&mut self, name: &str) -> Option<BoxedBuildPortableHasher> {
+            self.builders.remove(name)
+        }
+
+        /// The registered names, in sorted order.
+        pub fn names(&self) -> impl Iterator<Item = &str> + '_ {
+            self.builders.keys().map(String::as_str)
+        }
+    }
+}
@@ -0,0 +1,437 @@
+//! Alternative encodings, for matching externally specified hash formats and leaving out
+//! redundant length prefixes.
+//!
+//! The functions and [`BigEndian`] trait are used by the `#[portable_hash(encoding = "...")]`
+//! field attribute, and can be called from hand-written [`PortableHash`] impls.
+//!
+//! The hasher adapters change the encoding of every value hashed through them, for any wrapped
+//! hasher: [`BigEndianHasher`] writes integers in big-endian byte order, while
+//! [`CompactLengthsHasher`] and [`U32LengthsHasher`] change how length prefixes are written.
+//! Adapters can be nested, with length adapters on the outside:
+//! `U32LengthsHasher<BigEndianHasher<H>>` writes big-endian integers with 32-bit big-endian
+//! lengths. The adapters and their builders are also exported from the crate root.
+
+use crate::{
+    BuildPortableHasher, HashError, PathSegment, PortableHash, PortableHasher, PortableHasherOutput, TryPortableHash,
+};
+
+/// Write `value` as an unsigned LEB128 varint: 7 bits per byte, least significant group first,
+/// with the high bit set on every byte except the last.
+///
+/// # Example
+/// ```
+/// use portable_hash::{encoding, PortableHash, PortableHasher};
+///
+/// struct Frame {
+///     len: u32,
+/// }
+///
+/// // Equivalent to `#[derive(PortableHash)]` with `#[portable_hash(encoding = "varint")]` on `len`.
+/// impl PortableHash for Frame {
+///     fn portable_hash<H: PortableHasher>(&self, state: &mut H) {
+///         encoding::write_varint(state, u64::from(self.len));
+///     }
+/// }
+/// ```
+#[inline]
+pub fn write_varint<H: PortableHasher + ?Sized>(state: &mut H, mut value: u64) {
+    let mut buf = [0u8; 10];
+    let mut len = 0;
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            buf[len] = byte;
+            len += 1;
+            break;
+        }
+        buf[len] = byte | 0x80;
+        len += 1;
+    }
+    state.write(&buf[..len]);
+}
+
+/// Fixed-width types that can be written in big-endian byte order.
+///
+/// Implemented for the fixed-width integer types. `usize` and `isize` are excluded, as their
+/// width depends on the platform.
+pub trait BigEndian {
+    /// Write the value's big-endian bytes to the hasher, without a length prefix.
+    fn write_be<H: PortableHasher + ?Sized>(&self, state: &mut H);
+}
+
+macro_rules! impl_big_endian {
+    ($($ty:ty),*) => {
+        $(
+            impl BigEndian for $ty {
+                #[inline]
+                fn write_be<H: PortableHasher + ?Sized>(&self, state: &mut H) {
+                    state.write_short(self.to_be_bytes());
+                }
+            }
+        )*
+    };
+}
+
+impl_big_endian!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+
+/// Hash each element of an array in order, without the length prefix written by the array's
+/// [`PortableHash`] impl, using [`PortableHash::portable_hash_slice_unprefixed`]. Byte arrays are
+/// written with a single [`PortableHasher::write`].
+///
+/// The length of an array is fixed by its type, so two values of the same array type can't be
+/// confused without a prefix. This is used by `#[portable_hash(encoding = "fixed_len")]`, and
+/// produces different output from hashing the array directly.
+///
+/// # Example
+/// ```
+/// use portable_hash::{encoding, PortableHash, PortableHasher};
+///
+/// struct Block<const N: usize> {
+///     data: [u32; N],
+/// }
+///
+/// // Equivalent to `#[derive(PortableHash)]` with `#[portable_hash(encoding = "fixed_len")]`.
+/// impl<const N: usize> PortableHash for Block<N> {
+///     fn portable_hash<H: PortableHasher>(&self, state: &mut H) {
+///         encoding::hash_fixed_len(&self.data, state);
+///     }
+/// }
+/// ```
+#[inline]
+pub fn hash_fixed_len<T: PortableHash, H: PortableHasher, const LEN: usize>(array: &[T; LEN], state: &mut H) {
+    T::portable_hash_slice_unprefixed(array, state);
+}
+
+/// Hash a slice of [`TryPortableHash`] values, returning the first error.
+///
+/// `TryPortableHash` isn't implemented for slices, `Vec`s, or other containers of values that
+/// only implement `TryPortableHash`, so hand-written impls hash them with this function. The
+/// output is the same as hashing the slice with the default
+/// [`PortableHash::portable_hash_slice`]: a length prefix, followed by each element. The hasher's
+/// [`check`](PortableHasher::check) is called after each element, like between the fields of a
+/// `#[portable_hash(fallible)]` derive.
+///
+/// # Example
+/// ```
+/// use portable_hash::{encoding, HashError, PortableHasher, TryPortableHash};
+///
+/// struct Finite(f64);
+///
+/// impl TryPortableHash for Finite {
+///     type Error = HashError;
+///
+///     fn try_portable_hash<H: PortableHasher>(&self, state: &mut H) -> Result<(), HashError> {
+///         if !self.0.is_finite() {
+///             return Err(HashError::Invalid("non-finite floats can't be hashed"));
+///         }
+///         state.write_u64(self.0.to_bits());
+///         Ok(())
+///     }
+/// }
+///
+/// struct Series {
+///     points: Vec<Finite>,
+/// }
+///
+/// impl TryPortableHash for Series {
+///     type Error = HashError;
+///
+///     fn try_portable_hash<H: PortableHasher>(&self, state: &mut H) -> Result<(), HashError> {
+///         encoding::try_hash_slice(&self.points, state)
+///     }
+/// }
+/// ```
+#[inline]
+pub fn try_hash_slice<T, H>(data: &[T], state: &mut H) -> Result<(), T::Error>
+where
+    T: TryPortableHash,
+    T::Error: From<HashError>,
+    H: PortableHasher,
+{
+    try_hash_iter(data, state)
+}
+
+/// Hash the values of an iterator of [`TryPortableHash`] values as a sequence, returning the
+/// first error.
+///
+/// Writes the same length prefix and elements as [`try_hash_slice`], for containers that aren't
+/// slices, such as a `VecDeque`. The iterator must report its exact length, which is written
+/// before the first element.
+#[inline]
+pub fn try_hash_iter<'a, T, H, I>(items: I, state: &mut H) -> Result<(), T::Error>
+where
+    T: TryPortableHash + 'a,
+    T::Error: From<HashError>,
+    H: PortableHasher,
+    I: IntoIterator<Item = &'a T>,
+    I::IntoIter: ExactSizeIterator,
+{
+    let items = items.into_iter();
+    state.write_len_prefix(items.len());
+    for (i, item) in items.enumerate() {
+        if H::TRACING {
+            state.trace_enter(PathSegment::Index(i));
+        }
+        let result = item.try_portable_hash(state);
+        if H::TRACING {
+            state.trace_exit();
+        }
+        result?;
+        state.check()?;
+    }
+    Ok(())
+}
+
+/// Implement the `PortableHasher` methods that every adapter forwards unchanged to the wrapped
+/// hasher in field `$inner`, and the `write_str` and `write_bytes` methods that every adapter
+/// writes with its own length prefix.
+macro_rules! forward_adapter_methods {
+    ($inner:tt) => {
+        #[inline]
+        fn finish(&self) -> u64 {
+            self.$inner.finish()
+        }
+
+        #[inline]
+        fn write(&mut self, bytes: &[u8]) {
+            self.$inner.write(bytes);
+        }
+
+        #[inline]
+        fn write_short<const LEN: usize>(&mut self, bytes: [u8; LEN]) {
+            self.$inner.write_short(bytes);
+        }
+
+        #[inline]
+        fn write_str(&mut self, s: &str) {
+            self.write_len_prefix(s.len());
+            self.$inner.write(s.as_bytes());
+        }
+
+        #[inline]
+        fn write_bytes(&mut self, bytes: &[u8]) {
+            self.write_len_prefix(bytes.len());
+            self.$inner.write(bytes);
+        }
+
+        const TRACING: bool = H::TRACING;
+
+        #[inline]
+        fn trace_enter(&mut self, segment: PathSegment<'_>) {
+            self.$inner.trace_enter(segment);
+        }
+
+        #[inline]
+        fn trace_exit(&mut self) {
+            self.$inner.trace_exit();
+        }
+    };
+}
+
+/// Declare a hasher adapter's output forwarding and its [`BuildPortableHasher`].
+macro_rules! adapter_common {
+    ($name:ident, $build:ident, $inner:tt) => {
+        impl<H: PortableHasherOutput<O>, O> PortableHasherOutput<O> for $name<H> {
+            #[inline]
+            fn finalize(&self) -> O {
+                self.$inner.finalize()
+            }
+        }
+
+        #[doc = concat!("A [`BuildPortableHasher`] for [`", stringify!($name), "`], wrapping each hasher built by `B`.")]
+        #[derive(Clone, Debug, Default)]
+        pub struct $build<B>(pub B);
+
+        impl<B: BuildPortableHasher> BuildPortableHasher for $build<B> {
+            type PortableHasher = $name<B::PortableHasher>;
+
+            #[inline]
+            fn build_hasher(&self) -> Self::PortableHasher {
+                $name::new(self.0.build_hasher())
+            }
+        }
+    };
+}
+
+/// A hasher adapter that writes every integer in big-endian byte order.
+///
+/// The encoding is fully specified, independent of the wrapped hasher's own `write_*` overrides:
+/// - `u8` through `u128` and `i8` through `i128` are passed to the wrapped hasher's
+///   [`write_short`](PortableHasher::write_short) as their big-endian bytes.
+/// - `usize` and `isize` are widened to `u64` and `i64`, then written as big-endian bytes.
+/// - Length prefixes are written as a big-endian `u64`.
+/// - Strings and byte slices are written as a length prefix, followed by the bytes passed to the
+///   wrapped hasher's [`write`](PortableHasher::write).
+///
+/// [`write`](PortableHasher::write), [`write_short`](PortableHasher::write_short), the trace
+/// hooks, [`check`](PortableHasher::check), and the outputs are forwarded to the wrapped hasher
+/// unchanged.
+#[derive(Clone, Debug, Default)]
+pub struct BigEndianHasher<H>(pub H);
+
+impl<H> BigEndianHasher<H> {
+    /// Wrap a hasher, writing every integer to it in big-endian byte order.
+    pub fn new(hasher: H) -> Self {
+        Self(hasher)
+    }
+}
+
+macro_rules! write_be {
+    ($($method:ident: $ty:ty),*) => {
+        $(
+            #[inline]
+            fn $method(&mut self, i: $ty) {
+                self.0.write_short(i.to_be_bytes());
+            }
+        )*
+    };
+}
+
+impl<H: PortableHasher> PortableHasher for BigEndianHasher<H> {
+    forward_adapter_methods!(0);
+
+    write_be!(
+        write_u8: u8, write_u16: u16, write_u32: u32, write_u64: u64, write_u128: u128,
+        write_i8: i8, write_i16: i16, write_i32: i32, write_i64: i64, write_i128: i128
+    );
+
+    #[inline]
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64);
+    }
+
+    #[inline]
+    fn write_isize(&mut self, i: isize) {
+        self.write_i64(i as i64);
+    }
+
+    #[inline]
+    fn write_len_prefix(&mut self, len: usize) {
+        self.write_u64(len as u64);
+    }
+
+    #[inline]
+    fn check(&self) -> Result<(), HashError> {
+        self.0.check()
+    }
+}
+
+adapter_common!(BigEndianHasher, BigEndianBuildHasher, 0);
+
+/// A hasher adapter that writes length prefixes as unsigned LEB128 varints, as written by
+/// [`write_varint`].
+///
+/// Length prefixes are passed to the wrapped hasher's [`write`](PortableHasher::write). Strings
+/// and byte slices are written as a length prefix, followed by the bytes passed to `write`. All
+/// other methods, including the integer `write_*` methods, are forwarded to the wrapped hasher
+/// unchanged.
+#[derive(Clone, Debug, Default)]
+pub struct CompactLengthsHasher<H>(pub H);
+
+impl<H> CompactLengthsHasher<H> {
+    /// Wrap a hasher, writing length prefixes to it as varints.
+    pub fn new(hasher: H) -> Self {
+        Self(hasher)
+    }
+}
+
+/// Forward each listed integer method to the wrapped hasher in field `$inner`.
+macro_rules! forward_integers {
+    ($inner:tt) => {
+        forward_integers!(
+            $inner;
+            write_u8: u8, write_u16: u16, write_u32: u32, write_u64: u64, write_u128: u128,
+            write_usize: usize, write_i8: i8, write_i16: i16, write_i32: i32, write_i64: i64,
+            write_i128: i128, write_isize: isize
+        );
+    };
+    ($inner:tt; $($method:ident: $ty:ty),*) => {
+        $(
+            #[inline]
+            fn $method(&mut self, i: $ty) {
+                self.$inner.$method(i);
+            }
+        )*
+    };
+}
+
+impl<H: PortableHasher> PortableHasher for CompactLengthsHasher<H> {
+    forward_adapter_methods!(0);
+    forward_integers!(0);
+
+    #[inline]
+    fn write_len_prefix(&mut self, len: usize) {
+        write_varint(&mut self.0, len as u64);
+    }
+
+    #[inline]
+    fn check(&self) -> Result<(), HashError> {
+        self.0.check()
+    }
+}
+
+adapter_common!(CompactLengthsHasher, CompactLengthsBuildHasher, 0);
+
+/// A hasher adapter that writes length prefixes as a `u32`, through the wrapped hasher's
+/// [`write_u32`](PortableHasher::write_u32).
+///
+/// Strings and byte slices are written as a length prefix, followed by the bytes passed to the
+/// wrapped hasher's [`write`](PortableHasher::write). All other methods, including the integer
+/// `write_*` methods, are forwarded to the wrapped hasher unchanged.
+///
+/// A length greater than `u32::MAX` can't be represented, so it's recorded as a
+/// [`HashError::LengthTooLarge`] and reported by [`check`](PortableHasher::check), usually
+/// through [`BuildPortableHasher::try_hash_one`]. The length is written as `u32::MAX` instead,
+/// and the hash shouldn't be used.
+#[derive(Clone, Debug, Default)]
+pub struct U32LengthsHasher<H> {
+    hasher: H,
+    error: Option<HashError>,
+}
+
+impl<H> U32LengthsHasher<H> {
+    /// Wrap a hasher, writing length prefixes to it as `u32`s.
+    pub fn new(hasher: H) -> Self {
+        Self { hasher, error: None }
+    }
+
+    /// A reference to the wrapped hasher.
+    pub fn get_ref(&self) -> &H {
+        &self.hasher
+    }
+
+    /// Consume the `U32LengthsHasher`, returning the wrapped hasher.
+    pub fn into_inner(self) -> H {
+        self.hasher
+    }
+}
+
+impl<H: PortableHasher> PortableHasher for U32LengthsHasher<H> {
+    forward_adapter_methods!(hasher);
+    forward_integers!(hasher);
+
+    #[inline]
+    fn write_len_prefix(&mut self, len: usize) {
+        let len = u32::try_from(len).unwrap_or_else(|_| {
+            self.error.get_or_insert(HashError::LengthTooLarge {
+                len,
+                max_len: u32::MAX as usize,
+            });
+            u32::MAX
+        });
+        self.hasher.write_u32(len);
+    }
+
+    /// Returns the first length that didn't fit in a `u32`, then any error from the wrapped
+    /// hasher.
+    #[inline]
+    fn check(&self) -> Result<(), HashError> {
+        match self.error {
+            Some(error) => Err(error),
+            None => self.hasher.check(),
+        }
+    }
+}
+
+adapter_common!(U32LengthsHasher, U32LengthsBuildHasher, hasher);
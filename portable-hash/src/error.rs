@@ -0,0 +1,64 @@
+//! Errors reported by checked hashing.
+
+use core::fmt;
+
+/// An error that stopped a value from being hashed, reported by
+/// [`BuildPortableHasher::try_hash_one`](crate::BuildPortableHasher::try_hash_one).
+///
+/// [`PortableHash::portable_hash`](crate::PortableHash::portable_hash) can't return errors, so
+/// hashers that fail, such as [`LimitedHasher`](crate::LimitedHasher), record the error and report
+/// it from [`PortableHasher::check`](crate::PortableHasher::check). Values that fail validation
+/// return it from [`TryPortableHash::try_portable_hash`](crate::TryPortableHash::try_portable_hash).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum HashError {
+    /// More bytes were written than the byte budget allows.
+    ByteBudgetExceeded {
+        /// The maximum number of bytes, using the default [`PortableHasher`](crate::PortableHasher)
+        /// encodings.
+        max_bytes: u64,
+    },
+    /// A length prefix, string, or byte slice was longer than the maximum length.
+    LengthTooLarge {
+        /// The length that was written.
+        len: usize,
+        /// The maximum length.
+        max_len: usize,
+    },
+    /// More `write_*` calls were made than the call limit allows.
+    CallLimitExceeded {
+        /// The maximum number of calls.
+        max_calls: u64,
+    },
+    /// A value was nested deeper than the depth limit allows.
+    DepthExceeded {
+        /// The maximum depth.
+        max_depth: usize,
+    },
+    /// A value failed validation, and can't be hashed portably. Holds a description of the
+    /// problem, such as `"NaN can't be hashed"`.
+    Invalid(&'static str),
+}
+
+impl fmt::Display for HashError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HashError::ByteBudgetExceeded { max_bytes } => {
+                write!(f, "hash input exceeds the budget of {} bytes", max_bytes)
+            }
+            HashError::LengthTooLarge { len, max_len } => {
+                write!(f, "length {} exceeds the maximum hashed length of {}", len, max_len)
+            }
+            HashError::CallLimitExceeded { max_calls } => {
+                write!(f, "hash input exceeds the limit of {} writes", max_calls)
+            }
+            HashError::DepthExceeded { max_depth } => {
+                write!(f, "hash input is nested deeper than {} levels", max_depth)
+            }
+            HashError::Invalid(reason) => write!(f, "value can't be hashed: {}", reason),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for HashError {}
@@ -0,0 +1,138 @@
+//! `PortableHasher` impls for mutable references and boxes, forwarding to the hasher they point
+//! to.
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+use crate::{HashError, PathSegment, PortableHasher, PortableHasherOutput};
+
+/// Implement `PortableHasher` and `PortableHasherOutput` for a pointer type, forwarding every
+/// method to the pointed-to hasher, so that its overrides are kept.
+macro_rules! forward_hasher {
+    ($(#[$meta:meta])* $ty:ty) => {
+        $(#[$meta])*
+        impl<H: PortableHasher + ?Sized> PortableHasher for $ty {
+            #[inline]
+            fn finish(&self) -> u64 {
+                (**self).finish()
+            }
+
+            #[inline]
+            fn write(&mut self, bytes: &[u8]) {
+                (**self).write(bytes);
+            }
+
+            #[inline]
+            fn write_u8(&mut self, i: u8) {
+                (**self).write_u8(i);
+            }
+
+            #[inline]
+            fn write_u16(&mut self, i: u16) {
+                (**self).write_u16(i);
+            }
+
+            #[inline]
+            fn write_u32(&mut self, i: u32) {
+                (**self).write_u32(i);
+            }
+
+            #[inline]
+            fn write_u64(&mut self, i: u64) {
+                (**self).write_u64(i);
+            }
+
+            #[inline]
+            fn write_u128(&mut self, i: u128) {
+                (**self).write_u128(i);
+            }
+
+            #[inline]
+            fn write_usize(&mut self, i: usize) {
+                (**self).write_usize(i);
+            }
+
+            #[inline]
+            fn write_i8(&mut self, i: i8) {
+                (**self).write_i8(i);
+            }
+
+            #[inline]
+            fn write_i16(&mut self, i: i16) {
+                (**self).write_i16(i);
+            }
+
+            #[inline]
+            fn write_i32(&mut self, i: i32) {
+                (**self).write_i32(i);
+            }
+
+            #[inline]
+            fn write_i64(&mut self, i: i64) {
+                (**self).write_i64(i);
+            }
+
+            #[inline]
+            fn write_i128(&mut self, i: i128) {
+                (**self).write_i128(i);
+            }
+
+            #[inline]
+            fn write_isize(&mut self, i: isize) {
+                (**self).write_isize(i);
+            }
+
+            #[inline]
+            fn write_len_prefix(&mut self, len: usize) {
+                (**self).write_len_prefix(len);
+            }
+
+            #[inline]
+            fn write_str(&mut self, s: &str) {
+                (**self).write_str(s);
+            }
+
+            #[inline]
+            fn write_bytes(&mut self, bytes: &[u8]) {
+                (**self).write_bytes(bytes);
+            }
+
+            #[inline]
+            fn write_short<const LEN: usize>(&mut self, bytes: [u8; LEN]) {
+                (**self).write_short(bytes);
+            }
+
+            const TRACING: bool = H::TRACING;
+
+            #[inline]
+            fn trace_enter(&mut self, segment: PathSegment<'_>) {
+                (**self).trace_enter(segment);
+            }
+
+            #[inline]
+            fn trace_exit(&mut self) {
+                (**self).trace_exit();
+            }
+
+            #[inline]
+            fn check(&self) -> Result<(), HashError> {
+                (**self).check()
+            }
+        }
+
+        $(#[$meta])*
+        impl<H: PortableHasherOutput<O> + ?Sized, O> PortableHasherOutput<O> for $ty {
+            #[inline]
+            fn finalize(&self) -> O {
+                (**self).finalize()
+            }
+        }
+    };
+}
+
+forward_hasher!(&mut H);
+
+forward_hasher!(
+    #[cfg(feature = "alloc")]
+    alloc::boxed::Box<H>
+);
@@ -99,7 +99,7 @@ where
 impl PortableHash for CString {
     #[inline]
     fn portable_hash<H: PortableHasher>(&self, state: &mut H) {
-        (&**self).portable_hash(state);
+        self.as_c_str().portable_hash(state);
     }
 }
 
@@ -15,6 +15,12 @@ impl PortableHash for u8 {
     fn portable_hash_slice<H: PortableHasher>(data: &[u8], state: &mut H) {
         state.write_bytes(data)
     }
+
+    /// Write the bytes as a contiguous blob, which the length of the slice is fixed by.
+    #[inline]
+    fn portable_hash_slice_unprefixed<H: PortableHasher>(data: &[u8], state: &mut H) {
+        state.write(data)
+    }
 }
 
 macro_rules! impl_write {
@@ -5,12 +5,39 @@
 #![deny(unused_must_use)]
 #![deny(unsafe_code)]
 
+mod buffered;
+pub mod const_hash;
+mod counting;
+mod dyn_hash;
+pub mod encoding;
+mod error;
+mod forward;
 mod hash_impls;
+mod limited;
+mod macros;
 mod ord_impls;
+#[cfg(feature = "rayon")]
+pub mod par_hash;
+mod tee;
+mod trace;
 
 /// A derive macro for [`PortableHash`].
 pub use portable_hash_macros::PortableHash;
 
+pub use buffered::{BufferedBuildHasher, BufferedHasher};
+pub use counting::{CountingHasher, HashStats, NullHasher};
+pub use dyn_hash::{DynPortableHash, DynPortableHasher};
+pub use encoding::{BigEndianBuildHasher, BigEndianHasher, CompactLengthsBuildHasher, CompactLengthsHasher};
+pub use encoding::{U32LengthsBuildHasher, U32LengthsHasher};
+pub use error::HashError;
+#[cfg(feature = "alloc")]
+pub use dyn_hash::{BoxedBuildPortableHasher, BoxedPortableHasher, HasherRegistry};
+pub use limited::{HashLimits, LimitedBuildHasher, LimitedHasher};
+pub use tee::{TeeBuildHasher, TeeBuildHasher3, TeeBuildHasher4, TeeHasher, TeeHasher3, TeeHasher4};
+pub use trace::PathSegment;
+#[cfg(feature = "alloc")]
+pub use trace::{explain_diff, HashDiff, TraceHasher, TracedWrite};
+
 /// A trait for types that can be hashed in a portable way, inspired by [`std::hash::Hash`].
 ///
 /// This trait is similar to the standard library's `std::hash::Hash`, but it is designed to be
@@ -45,6 +72,13 @@ pub use portable_hash_macros::PortableHash;
 /// position-based discriminants or `#[portable_hash(discriminant_width = "u8")]` to control the
 /// write method. See the [`derive macro`](derive@PortableHash) docs for full details.
 ///
+/// **Unions** are hashed by their tag field and the active field it selects, declared with
+/// `#[portable_hash(union_tag = "tag", variants(a = 1, b = 2))]`. This relies on an `unsafe`
+/// contract, asserted with a hand-written `unsafe impl` of [`PortableHashUnion`].
+///
+/// **Generics** are bounded with `T: PortableHash` for each type parameter used by a field. Use
+/// `#[portable_hash(bound = "...")]` on the container or a field to override the inferred bounds.
+///
 /// # Example Manual Implementation
 /// ```
 /// use portable_hash::{PortableHash, PortableHasher};
@@ -80,12 +114,237 @@ pub trait PortableHash {
         // allow hash_slice to be overridden by some types to instead use the `write_bytes` method,
         // which allows the hasher to determine how/whether to handle a length prefix in these cases.
         state.write_len_prefix(data.len());
-        for item in data.iter() {
-            item.portable_hash(state);
+        Self::portable_hash_slice_unprefixed(data, state);
+    }
+
+    /// Feed a slice of this type into the given [`PortableHasher`] without a length prefix, for
+    /// slices whose length is fixed by their type, such as arrays hashed with
+    /// [`encoding::hash_fixed_len`].
+    ///
+    /// The default hashes each element in order. Types that override
+    /// [`portable_hash_slice`](Self::portable_hash_slice) with a faster encoding can override this
+    /// too, such as `u8`, which writes the bytes with a single [`PortableHasher::write`].
+    fn portable_hash_slice_unprefixed<H: PortableHasher>(data: &[Self], state: &mut H)
+    where
+        Self: Sized,
+    {
+        if H::TRACING {
+            for (i, item) in data.iter().enumerate() {
+                state.trace_enter(PathSegment::Index(i));
+                item.portable_hash(state);
+                state.trace_exit();
+            }
+        } else {
+            for item in data {
+                item.portable_hash(state);
+            }
         }
     }
 }
 
+/// A description of a type's hashing layout, generated by `#[derive(PortableHash)]`.
+///
+/// The schema is a single-line string listing everything the derived impl hashes: the field
+/// types as written in order, and for enums the discriminant mode, discriminant width, and each
+/// variant's discriminant and field types. Field and variant names are omitted, as renaming them
+/// doesn't change the hash output.
+///
+/// A change to the schema means the derived hash output may have changed, even for values that
+/// no test fixture happens to cover. `portable-hash-tester` can snapshot schemas with
+/// `FixtureDB::test_schema`. The schema only describes the type itself; the schemas of field
+/// types must be snapshotted separately.
+///
+/// # Example
+/// ```
+/// use portable_hash::{PortableHash, PortableSchema};
+///
+/// #[derive(PortableHash)]
+/// struct Order {
+///     id: u64,
+///     items: [(u32, u8); 4],
+/// }
+///
+/// assert_eq!(Order::PORTABLE_HASH_SCHEMA, "struct (u64, [(u32, u8); 4])");
+/// ```
+pub trait PortableSchema {
+    /// The hashing layout of this type.
+    const PORTABLE_HASH_SCHEMA: &'static str;
+}
+
+/// Hashes a foreign type `T` on behalf of a local mirror type, for types that can't implement
+/// [`PortableHash`] due to the orphan rule.
+///
+/// This is implemented by `#[derive(PortableHash)]` with `#[portable_hash(remote = "...")]` on a
+/// local definition that mirrors the fields or variants of the foreign type. The mirror is then
+/// used to hash fields of the foreign type with `#[portable_hash(with = "Mirror")]`, or to hash
+/// foreign values directly through the [`Remote`] wrapper.
+///
+/// # Example
+/// ```
+/// use portable_hash::{PortableHash, Remote};
+///
+/// mod other_crate {
+///     pub struct Point { pub x: i32, pub y: i32 }
+/// }
+///
+/// #[derive(PortableHash)]
+/// #[portable_hash(remote = "other_crate::Point")]
+/// struct PointDef {
+///     x: i32,
+///     y: i32,
+/// }
+///
+/// #[derive(PortableHash)]
+/// struct Shape {
+///     #[portable_hash(with = "PointDef")]
+///     origin: other_crate::Point,
+/// }
+///
+/// let point = other_crate::Point { x: 1, y: 2 };
+/// let _wrapped: Remote<PointDef, _> = Remote::new(&point);
+/// ```
+pub trait PortableHashRemote<T: ?Sized> {
+    /// Hash the foreign `value` using the specified hasher.
+    fn portable_hash_remote<H: PortableHasher>(value: &T, state: &mut H);
+}
+
+/// A newtype wrapper that implements [`PortableHash`] for a borrowed foreign `T`, using the
+/// [`PortableHashRemote`] impl of the mirror type `M`.
+pub struct Remote<'a, M, T: ?Sized> {
+    value: &'a T,
+    mirror: core::marker::PhantomData<M>,
+}
+
+impl<'a, M, T: ?Sized> Remote<'a, M, T> {
+    /// Wrap a foreign value to be hashed by the mirror type `M`.
+    pub fn new(value: &'a T) -> Self {
+        Self {
+            value,
+            mirror: core::marker::PhantomData,
+        }
+    }
+}
+
+impl<M: PortableHashRemote<T>, T: ?Sized> PortableHash for Remote<'_, M, T> {
+    #[inline]
+    fn portable_hash<H: PortableHasher>(&self, state: &mut H) {
+        M::portable_hash_remote(self.value, state);
+    }
+}
+
+/// A marker for unions whose fields `#[derive(PortableHash)]` may read, asserting the
+/// `union_tag` safety contract.
+///
+/// Reading a union field is `unsafe`, so the derive only accepts a union that implements this
+/// trait, which must be written by hand as an `unsafe impl`. The derived impl trusts the tag to
+/// identify the active field.
+///
+/// # Safety
+///
+/// For every value of the union that can be hashed:
+/// - The `union_tag` field is initialized. For a tag shared by all members, the union must be
+///   `#[repr(C)]` and every member must begin with the tag at offset zero.
+/// - Whenever the tag holds a value listed in `variants(...)`, the field listed for that value is
+///   initialized with a valid value of its type.
+///
+/// Unions with public fields can be built in safe code with any field initialized, so the
+/// contract usually needs private fields, or values that only come from FFI.
+///
+/// # Example
+/// ```ignore
+/// #[derive(PortableHash)]
+/// #[repr(C)]
+/// #[portable_hash(union_tag = "tag", variants(key = 1, mouse = 2))]
+/// union Event {
+///     tag: u8,
+///     key: KeyEvent,
+///     mouse: MouseEvent,
+/// }
+///
+/// // SAFETY: `Event`s are only created by the C library, which always sets `tag`, and sets the
+/// // matching member for tags 1 and 2.
+/// unsafe impl PortableHashUnion for Event {}
+/// ```
+#[allow(unsafe_code)]
+pub unsafe trait PortableHashUnion {}
+
+/// A trait for types that can only be hashed after passing validation, such as a float that
+/// must not be NaN, or a path that must be valid UTF-8.
+///
+/// Every [`PortableHash`] type implements `TryPortableHash`, with [`HashError`] as its error type,
+/// so types that implement `TryPortableHash` directly can't also implement `PortableHash`. Derive
+/// it with `#[derive(PortableHash)]` and `#[portable_hash(fallible)]`, which hashes every field
+/// with its `TryPortableHash` impl and returns the first error. A hasher that was passed to a
+/// failed `try_portable_hash` holds a partial input, so its output must not be used.
+///
+/// Error types must implement `From<HashError>` to be used with
+/// [`BuildPortableHasher::try_hash_one`], which also reports the errors of hashers such as
+/// [`LimitedHasher`].
+///
+/// Because of the blanket impl for `PortableHash` types, containers such as `Vec<T>`,
+/// `Option<T>`, `Box<T>`, tuples, and references don't implement `TryPortableHash` when `T` only
+/// implements `TryPortableHash`, so a derived `fallible` type can't have a `Vec<Finite>` field.
+/// Hash such fields in a hand-written impl, or a newtype's, with [`encoding::try_hash_slice`] or
+/// [`encoding::try_hash_iter`], which match the encoding of a slice.
+///
+/// # Example
+/// ```
+/// use portable_hash::{BuildPortableHasher, HashError, PortableHash, PortableHasher, TryPortableHash};
+///
+/// struct Finite(f64);
+///
+/// impl TryPortableHash for Finite {
+///     type Error = HashError;
+///
+///     fn try_portable_hash<H: PortableHasher>(&self, state: &mut H) -> Result<(), HashError> {
+///         if !self.0.is_finite() {
+///             return Err(HashError::Invalid("non-finite floats can't be hashed"));
+///         }
+///         state.write_u64(self.0.to_bits());
+///         Ok(())
+///     }
+/// }
+///
+/// #[derive(PortableHash)]
+/// #[portable_hash(fallible)]
+/// struct Reading {
+///     sensor: u32,
+///     value: Finite,
+/// }
+///
+/// # #[derive(Default)]
+/// # struct Fnv(u64);
+/// # impl PortableHasher for Fnv {
+/// #     fn finish(&self) -> u64 { self.0 }
+/// #     fn write(&mut self, bytes: &[u8]) {
+/// #         for b in bytes { self.0 = (self.0 ^ *b as u64).wrapping_mul(0x100000001b3); }
+/// #     }
+/// # }
+/// let build = portable_hash::DefaultBuildPortableHasher::<Fnv>::default();
+/// assert!(build.try_hash_one(&Reading { sensor: 1, value: Finite(0.5) }).is_ok());
+/// assert_eq!(
+///     build.try_hash_one(&Reading { sensor: 1, value: Finite(f64::NAN) }),
+///     Err(HashError::Invalid("non-finite floats can't be hashed")),
+/// );
+/// ```
+pub trait TryPortableHash {
+    /// The error returned when the value can't be hashed.
+    type Error;
+
+    /// Hash the value using the specified hasher, or return an error if it can't be hashed.
+    fn try_portable_hash<H: PortableHasher>(&self, state: &mut H) -> Result<(), Self::Error>;
+}
+
+impl<T: PortableHash + ?Sized> TryPortableHash for T {
+    type Error = HashError;
+
+    #[inline]
+    fn try_portable_hash<H: PortableHasher>(&self, state: &mut H) -> Result<(), HashError> {
+        self.portable_hash(state);
+        Ok(())
+    }
+}
+
 /// A trait marker that determines if a type's `Ord` implementation is guaranteed to be portable
 /// across platforms and compiler versions.
 ///
@@ -258,7 +517,7 @@ pub trait PortableHasher {
     /// Write a string slice to the hasher.
     #[inline]
     fn write_str(&mut self, s: &str) {
-        self.write_len_prefix(s.as_bytes().len());
+        self.write_len_prefix(s.len());
         self.write(s.as_bytes());
     }
 
@@ -302,6 +561,47 @@ pub trait PortableHasher {
     fn write_short<const LEN: usize>(&mut self, bytes: [u8; LEN]) {
         self.write(bytes.as_slice())
     }
+
+    /// Whether this hasher uses [`trace_enter`](Self::trace_enter) and
+    /// [`trace_exit`](Self::trace_exit). The default is `false`.
+    ///
+    /// The default [`PortableHash::portable_hash_slice`] only reports element indexes to hashers
+    /// that set this, so hashing slices costs nothing extra otherwise. Tracing hashers such as
+    /// [`TraceHasher`] set it to `true`, as does [`LimitedHasher`], which tracks the nesting depth.
+    /// Adapters that only forward the trace hooks to a wrapped hasher copy its value.
+    const TRACING: bool = false;
+
+    /// Called before hashing the value at `segment`, relative to the current path.
+    ///
+    /// Derived impls call this for every field and enum variant, and the default
+    /// [`PortableHash::portable_hash_slice`] calls it for every element when
+    /// [`TRACING`](Self::TRACING) is set. Each call is paired with a later
+    /// [`trace_exit`](Self::trace_exit). Tracing hashers such as [`TraceHasher`] use these calls
+    /// to attribute writes to a field path like `items[3].price`.
+    ///
+    /// Implementations must not let these calls affect the hash output: they are debugging aids,
+    /// and hand-written `PortableHash` impls are free to skip them. The default does nothing, and
+    /// is compiled away for hashers that don't override it.
+    #[inline]
+    fn trace_enter(&mut self, segment: PathSegment<'_>) {
+        let _ = segment;
+    }
+
+    /// Called after hashing the value passed to the matching
+    /// [`trace_enter`](Self::trace_enter). The default does nothing.
+    #[inline]
+    fn trace_exit(&mut self) {}
+
+    /// Report whether hashing has failed.
+    ///
+    /// [`PortableHash::portable_hash`] can't return errors, so hashers that can fail, such as
+    /// [`LimitedHasher`], record the first error and return it here.
+    /// [`BuildPortableHasher::try_hash_one`] calls this after hashing, and adapters that wrap
+    /// other hashers forward it. The default returns `Ok(())`.
+    #[inline]
+    fn check(&self) -> Result<(), HashError> {
+        Ok(())
+    }
 }
 
 /// An extension to [`PortableHasher`] for hashers that can produce output type `T`.
@@ -363,6 +663,27 @@ pub trait BuildPortableHasher {
         x.portable_hash(&mut hasher);
         hasher.finalize()
     }
+
+    /// Hash an object that may fail to hash, returning a u64 hash value, or the error returned by
+    /// the object's [`TryPortableHash`] impl or reported by the hasher's
+    /// [`check`](PortableHasher::check).
+    ///
+    /// Use this with fallible types, and with hashers that can fail, such as [`LimitedHasher`],
+    /// which limits the resources used to hash untrusted input. Every [`PortableHash`] type can be
+    /// hashed this way, with [`HashError`] as the error type.
+    ///
+    /// Unlike [`hash_one`](Self::hash_one), this takes the object by reference, as references to
+    /// fallible types don't implement `TryPortableHash`.
+    fn try_hash_one<T>(&self, x: &T) -> Result<u64, T::Error>
+    where
+        T: TryPortableHash + ?Sized,
+        T::Error: From<HashError>,
+    {
+        let mut hasher = self.build_hasher();
+        x.try_portable_hash(&mut hasher)?;
+        hasher.check()?;
+        Ok(hasher.finish())
+    }
 }
 
 /// A default implementation of [`BuildPortableHasher`] that instantiates the [`PortableHasher`]
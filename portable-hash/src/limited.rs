@@ -0,0 +1,276 @@
+//! A hasher that limits the resources used to hash untrusted input.
+
+use crate::{BuildPortableHasher, HashError, PathSegment, PortableHasher, PortableHasherOutput};
+
+/// The limits enforced by a [`LimitedHasher`]. The default is unlimited.
+///
+/// Sizes and calls are counted the same way as by [`CountingHasher`](crate::CountingHasher).
+///
+/// # Example
+/// ```
+/// use portable_hash::HashLimits;
+///
+/// let limits = HashLimits { max_bytes: 1 << 20, max_len: 4096, ..HashLimits::default() };
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct HashLimits {
+    /// The maximum number of bytes written, using the default [`PortableHasher`] encodings.
+    pub max_bytes: u64,
+    /// The maximum length passed to [`write_len_prefix`](PortableHasher::write_len_prefix),
+    /// [`write_str`](PortableHasher::write_str), or [`write_bytes`](PortableHasher::write_bytes):
+    /// the number of elements in a collection, or of bytes in a string or byte slice.
+    pub max_len: usize,
+    /// The maximum number of `write_*` calls.
+    pub max_calls: u64,
+    /// The maximum nesting depth: the number of [`PathSegment`]s in the path of the value being
+    /// hashed, as reported to [`trace_enter`](PortableHasher::trace_enter). Each field and enum
+    /// variant of a derived type adds one level, as does each element of a slice, array, or `Vec`.
+    pub max_depth: usize,
+}
+
+impl Default for HashLimits {
+    fn default() -> Self {
+        Self {
+            max_bytes: u64::MAX,
+            max_len: usize::MAX,
+            max_calls: u64::MAX,
+            max_depth: usize::MAX,
+        }
+    }
+}
+
+/// A [`PortableHasher`] adapter that fails once the input exceeds its [`HashLimits`], for hashing
+/// untrusted input.
+///
+/// [`PortableHash::portable_hash`](crate::PortableHash::portable_hash) can't return errors, so the
+/// first limit exceeded is recorded and reported by [`check`](PortableHasher::check), usually
+/// through [`BuildPortableHasher::try_hash_one`]. Every write after that is dropped instead of
+/// being forwarded to the wrapped hasher, so an expensive hasher does no further work.
+/// `portable_hash` can't be interrupted, so it still traverses the rest of the value.
+/// [`TryPortableHash`](crate::TryPortableHash) impls derived with `#[portable_hash(fallible)]`
+/// call `check` after each field, and return the error as soon as a limit is exceeded.
+///
+/// The depth is tracked through the [`trace_enter`](PortableHasher::trace_enter) and
+/// [`trace_exit`](PortableHasher::trace_exit) hooks, so `LimitedHasher` sets
+/// [`TRACING`](PortableHasher::TRACING), and slices report each element. Trait objects forward
+/// the hooks, so values hashed through `dyn DynPortableHash` are counted too. Hand-written
+/// `PortableHash` impls that don't call the hooks add no depth.
+///
+/// Lengths are only checked when they're written through `write_len_prefix`, `write_str`, or
+/// `write_bytes`, so adapters that turn every write into a [`write`](PortableHasher::write) call,
+/// such as [`BufferedHasher`](crate::BufferedHasher), belong inside the `LimitedHasher`.
+///
+/// Writes within the limits, the trace hooks, and the outputs are forwarded to the wrapped hasher
+/// unchanged. After a failure, [`finish`](PortableHasher::finish) and
+/// [`finalize`](PortableHasherOutput::finalize) return the wrapped hasher's output for the
+/// writes before the failure, which shouldn't be used.
+///
+/// # Example
+/// ```ignore
+/// let limits = HashLimits { max_bytes: 1 << 20, max_len: 4096, ..HashLimits::default() };
+/// let build = LimitedBuildHasher::new(Sha256BuildHasher::default(), limits);
+/// match build.try_hash_one(&payload) {
+///     Ok(hash) => store(hash),
+///     Err(HashError::LengthTooLarge { .. }) => reject(payload),
+///     Err(error) => return Err(error.into()),
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct LimitedHasher<H> {
+    hasher: H,
+    limits: HashLimits,
+    bytes: u64,
+    calls: u64,
+    depth: usize,
+    error: Option<HashError>,
+}
+
+impl<H> LimitedHasher<H> {
+    /// Wrap a hasher, enforcing `limits` on the writes forwarded to it.
+    pub fn new(hasher: H, limits: HashLimits) -> Self {
+        Self {
+            hasher,
+            limits,
+            bytes: 0,
+            calls: 0,
+            depth: 0,
+            error: None,
+        }
+    }
+
+    /// The limits being enforced.
+    pub fn limits(&self) -> &HashLimits {
+        &self.limits
+    }
+
+    /// A reference to the wrapped hasher.
+    pub fn get_ref(&self) -> &H {
+        &self.hasher
+    }
+
+    /// Consume the `LimitedHasher`, returning the wrapped hasher.
+    pub fn into_inner(self) -> H {
+        self.hasher
+    }
+
+    /// Count a call writing `bytes` bytes, returning whether it can be forwarded.
+    #[inline]
+    fn admit(&mut self, bytes: u64) -> bool {
+        if self.error.is_some() {
+            return false;
+        }
+
+        self.calls += 1;
+        self.bytes = self.bytes.saturating_add(bytes);
+        if self.calls > self.limits.max_calls {
+            self.error = Some(HashError::CallLimitExceeded {
+                max_calls: self.limits.max_calls,
+            });
+        } else if self.bytes > self.limits.max_bytes {
+            self.error = Some(HashError::ByteBudgetExceeded {
+                max_bytes: self.limits.max_bytes,
+            });
+        }
+        self.error.is_none()
+    }
+
+    /// Count a call writing `len` as a length, and `bytes` bytes in total.
+    #[inline]
+    fn admit_len(&mut self, len: usize, bytes: u64) -> bool {
+        if self.error.is_none() && len > self.limits.max_len {
+            self.error = Some(HashError::LengthTooLarge {
+                len,
+                max_len: self.limits.max_len,
+            });
+        }
+        self.admit(bytes)
+    }
+}
+
+macro_rules! limit_int_writes {
+    ($($method:ident: $ty:ty = $size:expr),* $(,)?) => {
+        $(
+            #[inline]
+            fn $method(&mut self, i: $ty) {
+                if self.admit($size) {
+                    self.hasher.$method(i);
+                }
+            }
+        )*
+    };
+}
+
+impl<H: PortableHasher> PortableHasher for LimitedHasher<H> {
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.hasher.finish()
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        if self.admit(bytes.len() as u64) {
+            self.hasher.write(bytes);
+        }
+    }
+
+    limit_int_writes!(
+        write_u8: u8 = 1,
+        write_u16: u16 = 2,
+        write_u32: u32 = 4,
+        write_u64: u64 = 8,
+        write_u128: u128 = 16,
+        write_usize: usize = 8,
+        write_i8: i8 = 1,
+        write_i16: i16 = 2,
+        write_i32: i32 = 4,
+        write_i64: i64 = 8,
+        write_i128: i128 = 16,
+        write_isize: isize = 8,
+    );
+
+    #[inline]
+    fn write_len_prefix(&mut self, len: usize) {
+        if self.admit_len(len, 8) {
+            self.hasher.write_len_prefix(len);
+        }
+    }
+
+    #[inline]
+    fn write_str(&mut self, s: &str) {
+        if self.admit_len(s.len(), 8 + s.len() as u64) {
+            self.hasher.write_str(s);
+        }
+    }
+
+    #[inline]
+    fn write_bytes(&mut self, bytes: &[u8]) {
+        if self.admit_len(bytes.len(), 8 + bytes.len() as u64) {
+            self.hasher.write_bytes(bytes);
+        }
+    }
+
+    #[inline]
+    fn write_short<const LEN: usize>(&mut self, bytes: [u8; LEN]) {
+        if self.admit(LEN as u64) {
+            self.hasher.write_short(bytes);
+        }
+    }
+
+    const TRACING: bool = true;
+
+    #[inline]
+    fn trace_enter(&mut self, segment: PathSegment<'_>) {
+        self.depth += 1;
+        if self.error.is_none() && self.depth > self.limits.max_depth {
+            self.error = Some(HashError::DepthExceeded {
+                max_depth: self.limits.max_depth,
+            });
+        }
+        self.hasher.trace_enter(segment);
+    }
+
+    #[inline]
+    fn trace_exit(&mut self) {
+        self.depth = self.depth.saturating_sub(1);
+        self.hasher.trace_exit();
+    }
+
+    #[inline]
+    fn check(&self) -> Result<(), HashError> {
+        match self.error {
+            Some(error) => Err(error),
+            None => self.hasher.check(),
+        }
+    }
+}
+
+impl<H: PortableHasherOutput<O>, O> PortableHasherOutput<O> for LimitedHasher<H> {
+    #[inline]
+    fn finalize(&self) -> O {
+        self.hasher.finalize()
+    }
+}
+
+/// A [`BuildPortableHasher`] for [`LimitedHasher`], wrapping each hasher built by `B` with the
+/// same limits.
+#[derive(Clone, Debug, Default)]
+pub struct LimitedBuildHasher<B> {
+    builder: B,
+    limits: HashLimits,
+}
+
+impl<B> LimitedBuildHasher<B> {
+    /// Create a builder that enforces `limits` on every hasher built by `builder`.
+    pub fn new(builder: B, limits: HashLimits) -> Self {
+        Self { builder, limits }
+    }
+}
+
+impl<B: BuildPortableHasher> BuildPortableHasher for LimitedBuildHasher<B> {
+    type PortableHasher = LimitedHasher<B::PortableHasher>;
+
+    #[inline]
+    fn build_hasher(&self) -> Self::PortableHasher {
+        LimitedHasher::new(self.builder.build_hasher(), self.limits)
+    }
+}
@@ -0,0 +1,48 @@
+//! Declarative macros for implementing [`PortableHash`](crate::PortableHash) by hand.
+
+/// Implement [`PortableHash`](crate::PortableHash) by hashing a list of fields in order.
+///
+/// A lightweight alternative to `#[derive(PortableHash)]` for local newtypes and simple structs,
+/// equivalent to the derive for the listed fields. Fields are hashed in the order they are
+/// listed, so reordering the list changes the hash output. Generic parameters listed in `<...>`
+/// are bounded by `PortableHash`.
+///
+/// # Example
+/// ```
+/// use portable_hash::impl_portable_hash;
+///
+/// struct UserId(u64);
+/// struct Point { x: i32, y: i32 }
+/// struct Wrapper<T>(T);
+///
+/// impl_portable_hash!(UserId { 0 });
+/// impl_portable_hash!(Point { x, y });
+/// impl_portable_hash!(<T> Wrapper<T> { 0 });
+/// ```
+#[macro_export]
+macro_rules! impl_portable_hash {
+    (<$($generic:ident),+ $(,)?> $ty:ty { $($field:tt),* $(,)? }) => {
+        impl<$($generic: $crate::PortableHash),+> $crate::PortableHash for $ty {
+            #[inline]
+            fn portable_hash<H: $crate::PortableHasher>(&self, state: &mut H) {
+                $(
+                    state.trace_enter($crate::PathSegment::Field(stringify!($field)));
+                    $crate::PortableHash::portable_hash(&self.$field, state);
+                    state.trace_exit();
+                )*
+            }
+        }
+    };
+    ($ty:ty { $($field:tt),* $(,)? }) => {
+        impl $crate::PortableHash for $ty {
+            #[inline]
+            fn portable_hash<H: $crate::PortableHasher>(&self, state: &mut H) {
+                $(
+                    state.trace_enter($crate::PathSegment::Field(stringify!($field)));
+                    $crate::PortableHash::portable_hash(&self.$field, state);
+                    state.trace_exit();
+                )*
+            }
+        }
+    };
+}
@@ -0,0 +1,133 @@
+//! Parallel tree-mode hashing of large slices, with the `rayon` feature.
+//!
+//! [`par_hash`] and [`par_finalize`] split a slice into chunks of [`PAR_HASH_CHUNK_LEN`]
+//! elements, hash each chunk on the rayon thread pool with its own hasher from a
+//! [`BuildPortableHasher`], and combine the chunk digests in a binary Merkle tree:
+//!
+//! 1. The slice is split into chunks of `PAR_HASH_CHUNK_LEN` elements, in order. The last chunk
+//!    may be shorter. An empty slice is a single empty chunk.
+//! 2. Each chunk's leaf digest is the hash of `(0u8, chunk)`, with the chunk hashed as a `[T]`
+//!    slice: a length prefix, then its elements.
+//! 3. While there's more than one digest, adjacent pairs of digests are combined into the hash of
+//!    `(1u8, left, right)`. An odd digest at the end of a level is carried up to the next level
+//!    unchanged.
+//! 4. The last digest left is the hash of the slice.
+//!
+//! The tree depends only on the length of the slice, so the hash is the same whatever the number
+//! of threads. It differs from the hash of the slice by [`BuildPortableHasher::hash_one`], even for
+//! slices shorter than a chunk, so a value must always be hashed the same way. Changing
+//! `PAR_HASH_CHUNK_LEN` would change every tree hash, so it's part of the encoding, and is fixed.
+//!
+//! rayon links the standard library, but the `rayon` feature doesn't enable this crate's `std`
+//! feature, so the `PortableHash` impls of std types still need `std` enabled alongside it.
+//!
+//! # Example
+//! ```
+//! use portable_hash::par_hash::{par_hash, PAR_HASH_CHUNK_LEN};
+//! use portable_hash::const_hash::FnvBuildHasher;
+//! use portable_hash::BuildPortableHasher;
+//!
+//! let build = FnvBuildHasher::default();
+//! let records: Vec<(u32, u64)> = (0..5000).map(|i| (i, u64::from(i) * 3)).collect();
+//! let hash = par_hash(&build, &records);
+//!
+//! // The same tree, hashed on one thread.
+//! let leaves: Vec<u64> = records
+//!     .chunks(PAR_HASH_CHUNK_LEN)
+//!     .map(|chunk| build.hash_one((0u8, chunk)))
+//!     .collect();
+//! assert_eq!(leaves.len(), 5);
+//! let a = build.hash_one((1u8, leaves[0], leaves[1]));
+//! let b = build.hash_one((1u8, leaves[2], leaves[3]));
+//! let ab = build.hash_one((1u8, a, b));
+//! assert_eq!(hash, build.hash_one((1u8, ab, leaves[4])));
+//! ```
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+
+use rayon::prelude::*;
+
+use crate::{BuildPortableHasher, PortableHash, PortableHasher, PortableHasherOutput};
+
+/// The number of elements hashed into each leaf of the tree by [`par_hash`] and [`par_finalize`].
+///
+/// This is part of the tree encoding, and doesn't depend on the number of threads or CPUs.
+pub const PAR_HASH_CHUNK_LEN: usize = 1024;
+
+/// The tag hashed before the elements of a leaf.
+const LEAF_TAG: u8 = 0;
+
+/// The tag hashed before the two child digests of a node.
+const NODE_TAG: u8 = 1;
+
+/// Hash a slice in parallel in tree mode, returning the [`finish`](PortableHasher::finish) output
+/// of the root hasher. Every digest in the tree is a `u64`.
+///
+/// See the [module docs](self) for the tree encoding.
+pub fn par_hash<B, T>(build: &B, data: &[T]) -> u64
+where
+    B: BuildPortableHasher + Sync,
+    T: PortableHash + Sync,
+{
+    tree_hash(build, data, |hasher: &B::PortableHasher| hasher.finish())
+}
+
+/// Hash a slice in parallel in tree mode, returning the hasher-specific output of the root
+/// hasher. Every digest in the tree is an `O`, such as a full `[u8; 32]` digest, so the tree is as
+/// collision resistant as the hasher's output.
+///
+/// See the [module docs](self) for the tree encoding.
+pub fn par_finalize<O, B, T>(build: &B, data: &[T]) -> O
+where
+    O: PortableHash + Send + Sync,
+    B: BuildPortableHasher + Sync,
+    B::PortableHasher: PortableHasherOutput<O>,
+    T: PortableHash + Sync,
+{
+    tree_hash(build, data, |hasher: &B::PortableHasher| hasher.finalize())
+}
+
+/// Hash the leaves and nodes of the tree, reading each digest from its hasher with `digest`.
+fn tree_hash<B, T, D, F>(build: &B, data: &[T], digest: F) -> D
+where
+    B: BuildPortableHasher + Sync,
+    T: PortableHash + Sync,
+    D: PortableHash + Send + Sync,
+    F: Fn(&B::PortableHasher) -> D + Sync,
+{
+    let mut level: Vec<D> = if data.is_empty() {
+        let mut hasher = build.build_hasher();
+        hasher.write_u8(LEAF_TAG);
+        data.portable_hash(&mut hasher);
+        alloc::vec![digest(&hasher)]
+    } else {
+        data.par_chunks(PAR_HASH_CHUNK_LEN)
+            .map(|chunk| {
+                let mut hasher = build.build_hasher();
+                hasher.write_u8(LEAF_TAG);
+                chunk.portable_hash(&mut hasher);
+                digest(&hasher)
+            })
+            .collect()
+    };
+
+    while level.len() > 1 {
+        let carried = if level.len() % 2 == 1 { level.pop() } else { None };
+        let mut next: Vec<D> = level
+            .par_chunks(2)
+            .map(|pair| {
+                let mut hasher = build.build_hasher();
+                hasher.write_u8(NODE_TAG);
+                pair[0].portable_hash(&mut hasher);
+                pair[1].portable_hash(&mut hasher);
+                digest(&hasher)
+            })
+            .collect();
+        next.extend(carried);
+        level = next;
+    }
+
+    level.pop().expect("the tree always has at least one leaf")
+}
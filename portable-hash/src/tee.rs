@@ -0,0 +1,178 @@
+//! Hashers that fan every write out to several hashers, to compute several hashes of a value in
+//! one traversal.
+
+use crate::{BuildPortableHasher, HashError, PathSegment, PortableHasher, PortableHasherOutput};
+
+macro_rules! tee_hasher {
+    (
+        $(#[$meta:meta])*
+        $name:ident, $build:ident, $($param:ident $output:ident $index:tt),+
+    ) => {
+        $(#[$meta])*
+        #[derive(Clone, Debug, Default)]
+        pub struct $name<$($param),+>($(pub $param),+);
+
+        impl<$($param: PortableHasher),+> PortableHasher for $name<$($param),+> {
+            /// Returns the `finish` value of the first hasher.
+            #[inline]
+            fn finish(&self) -> u64 {
+                self.0.finish()
+            }
+
+            #[inline]
+            fn write(&mut self, bytes: &[u8]) {
+                $(self.$index.write(bytes);)+
+            }
+
+            #[inline]
+            fn write_u8(&mut self, i: u8) {
+                $(self.$index.write_u8(i);)+
+            }
+
+            #[inline]
+            fn write_u16(&mut self, i: u16) {
+                $(self.$index.write_u16(i);)+
+            }
+
+            #[inline]
+            fn write_u32(&mut self, i: u32) {
+                $(self.$index.write_u32(i);)+
+            }
+
+            #[inline]
+            fn write_u64(&mut self, i: u64) {
+                $(self.$index.write_u64(i);)+
+            }
+
+            #[inline]
+            fn write_u128(&mut self, i: u128) {
+                $(self.$index.write_u128(i);)+
+            }
+
+            #[inline]
+            fn write_usize(&mut self, i: usize) {
+                $(self.$index.write_usize(i);)+
+            }
+
+            #[inline]
+            fn write_i8(&mut self, i: i8) {
+                $(self.$index.write_i8(i);)+
+            }
+
+            #[inline]
+            fn write_i16(&mut self, i: i16) {
+                $(self.$index.write_i16(i);)+
+            }
+
+            #[inline]
+            fn write_i32(&mut self, i: i32) {
+                $(self.$index.write_i32(i);)+
+            }
+
+            #[inline]
+            fn write_i64(&mut self, i: i64) {
+                $(self.$index.write_i64(i);)+
+            }
+
+            #[inline]
+            fn write_i128(&mut self, i: i128) {
+                $(self.$index.write_i128(i);)+
+            }
+
+            #[inline]
+            fn write_isize(&mut self, i: isize) {
+                $(self.$index.write_isize(i);)+
+            }
+
+            #[inline]
+            fn write_len_prefix(&mut self, len: usize) {
+                $(self.$index.write_len_prefix(len);)+
+            }
+
+            #[inline]
+            fn write_str(&mut self, s: &str) {
+                $(self.$index.write_str(s);)+
+            }
+
+            #[inline]
+            fn write_bytes(&mut self, bytes: &[u8]) {
+                $(self.$index.write_bytes(bytes);)+
+            }
+
+            #[inline]
+            fn write_short<const LEN: usize>(&mut self, bytes: [u8; LEN]) {
+                $(self.$index.write_short(bytes);)+
+            }
+
+            const TRACING: bool = $($param::TRACING)||+;
+
+            #[inline]
+            fn trace_enter(&mut self, segment: PathSegment<'_>) {
+                $(self.$index.trace_enter(segment);)+
+            }
+
+            #[inline]
+            fn trace_exit(&mut self) {
+                $(self.$index.trace_exit();)+
+            }
+
+            /// Returns the first error reported by any hasher.
+            #[inline]
+            fn check(&self) -> Result<(), HashError> {
+                $(self.$index.check()?;)+
+                Ok(())
+            }
+        }
+
+        impl<$($param, $output),+> PortableHasherOutput<($($output,)+)> for $name<$($param),+>
+        where
+            $($param: PortableHasherOutput<$output>),+
+        {
+            #[inline]
+            fn finalize(&self) -> ($($output,)+) {
+                ($(self.$index.finalize(),)+)
+            }
+        }
+
+        #[doc = concat!("A [`BuildPortableHasher`] for [`", stringify!($name), "`], built from one builder per hasher.")]
+        #[derive(Clone, Debug, Default)]
+        pub struct $build<$($param),+>($(pub $param),+);
+
+        impl<$($param: BuildPortableHasher),+> BuildPortableHasher for $build<$($param),+> {
+            type PortableHasher = $name<$($param::PortableHasher),+>;
+
+            #[inline]
+            fn build_hasher(&self) -> Self::PortableHasher {
+                $name($(self.$index.build_hasher()),+)
+            }
+        }
+    };
+}
+
+tee_hasher!(
+    /// A [`PortableHasher`] that forwards every call to two hashers, to compute both hashes of a
+    /// value in a single traversal.
+    ///
+    /// Every method is forwarded, including `write_len_prefix`, `write_str`, `write_bytes`, and
+    /// `write_short`, so each hasher's overrides are used and each produces exactly the output it
+    /// would on its own. [`finish`](PortableHasher::finish) returns the first hasher's `finish`,
+    /// while [`finalize`](PortableHasherOutput::finalize) returns a tuple of every hasher's
+    /// output. Use [`TeeHasher3`] and [`TeeHasher4`] for more hashers.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let build = TeeBuildHasher(FastBuildHasher::default(), Sha256BuildHasher::default());
+    /// let (key, digest): (u64, [u8; 32]) = build.finalize_one(&record);
+    /// ```
+    TeeHasher, TeeBuildHasher, A OA 0, B OB 1
+);
+
+tee_hasher!(
+    /// A [`PortableHasher`] that forwards every call to three hashers. See [`TeeHasher`].
+    TeeHasher3, TeeBuildHasher3, A OA 0, B OB 1, C OC 2
+);
+
+tee_hasher!(
+    /// A [`PortableHasher`] that forwards every call to four hashers. See [`TeeHasher`].
+    TeeHasher4, TeeBuildHasher4, A OA 0, B OB 1, C OC 2, D OD 3
+);
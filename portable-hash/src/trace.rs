@@ -0,0 +1,198 @@
+//! Field-path tracing, for finding out which part of a value changed its hash.
+
+/// One step of the path from a hashed value to a nested value.
+///
+/// Reported to [`PortableHasher::trace_enter`](crate::PortableHasher::trace_enter) by derived
+/// impls, and by the default [`PortableHash::portable_hash_slice`](crate::PortableHash::portable_hash_slice)
+/// for hashers that set [`PortableHasher::TRACING`](crate::PortableHasher::TRACING).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PathSegment<'a> {
+    /// A struct, variant, or union field: its name, or its position for tuple fields. Rendered
+    /// as `.price` or `.0`.
+    Field(&'a str),
+    /// An enum variant, entered before its discriminant is written. Rendered as `::Paid`.
+    Variant(&'a str),
+    /// An element of a slice, array, or `Vec`. Rendered as `[3]`.
+    Index(usize),
+}
+
+#[cfg(feature = "alloc")]
+pub use self::recorder::*;
+
+#[cfg(feature = "alloc")]
+mod recorder {
+    extern crate alloc;
+    use alloc::{string::String, vec::Vec};
+    use core::fmt::{self, Write};
+
+    use super::PathSegment;
+    use crate::{PortableHash, PortableHasher};
+
+    /// A single write recorded by a [`TraceHasher`].
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct TracedWrite {
+        /// The path of the value being hashed when the write happened, such as `items[3].price`.
+        /// Empty for writes made outside of any traced field.
+        pub path: String,
+        /// The bytes written, using the default [`PortableHasher`] encodings.
+        pub bytes: Vec<u8>,
+    }
+
+    /// A [`PortableHasher`] that records every write along with the field path it came from.
+    ///
+    /// `TraceHasher` doesn't compute a hash: [`finish`](PortableHasher::finish) always returns 0.
+    /// It's intended for debugging unexpected hash changes, usually through [`explain_diff`].
+    ///
+    /// Paths are only as detailed as the `PortableHash` impls make them. Derived impls report
+    /// every field and variant, and slices, arrays, and `Vec`s report every element index. Other
+    /// collections, such as maps and sets, are recorded under the path of the collection itself.
+    #[derive(Clone, Debug, Default)]
+    pub struct TraceHasher {
+        path: String,
+        /// The length of `path` before each segment still being traced was appended.
+        marks: Vec<usize>,
+        writes: Vec<TracedWrite>,
+    }
+
+    impl TraceHasher {
+        /// Create an empty `TraceHasher`.
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// The writes recorded so far, in order.
+        pub fn writes(&self) -> &[TracedWrite] {
+            &self.writes
+        }
+
+        /// Consume the hasher, returning the recorded writes.
+        pub fn into_writes(self) -> Vec<TracedWrite> {
+            self.writes
+        }
+    }
+
+    impl PortableHasher for TraceHasher {
+        const TRACING: bool = true;
+
+        fn finish(&self) -> u64 {
+            0
+        }
+
+        fn write(&mut self, bytes: &[u8]) {
+            self.writes.push(TracedWrite {
+                path: self.path.clone(),
+                bytes: bytes.to_vec(),
+            });
+        }
+
+        fn trace_enter(&mut self, segment: PathSegment<'_>) {
+            self.marks.push(self.path.len());
+            let first = self.path.is_empty();
+            // Writing to a String can't fail.
+            let _ = match segment {
+                PathSegment::Field(name) if first => self.path.write_str(name),
+                PathSegment::Field(name) => write!(self.path, ".{}", name),
+                PathSegment::Variant(name) if first => self.path.write_str(name),
+                PathSegment::Variant(name) => write!(self.path, "::{}", name),
+                PathSegment::Index(index) => write!(self.path, "[{}]", index),
+            };
+        }
+
+        fn trace_exit(&mut self) {
+            if let Some(mark) = self.marks.pop() {
+                self.path.truncate(mark);
+            }
+        }
+    }
+
+    /// The first difference between the write streams of two values, as found by
+    /// [`explain_diff`].
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct HashDiff {
+        /// The position of the first differing write in both streams.
+        pub index: usize,
+        /// The write made by the left value, or `None` if its stream ended first.
+        pub left: Option<TracedWrite>,
+        /// The write made by the right value, or `None` if its stream ended first.
+        pub right: Option<TracedWrite>,
+    }
+
+    impl HashDiff {
+        /// The path of the first differing write, preferring the left value's path.
+        pub fn path(&self) -> &str {
+            match (&self.left, &self.right) {
+                (Some(write), _) | (None, Some(write)) => &write.path,
+                (None, None) => "",
+            }
+        }
+    }
+
+    impl fmt::Display for HashDiff {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            fn side(f: &mut fmt::Formatter<'_>, write: &Option<TracedWrite>) -> fmt::Result {
+                match write {
+                    Some(write) if write.path.is_empty() => write!(f, "{:?} at the root", write.bytes),
+                    Some(write) => write!(f, "{:?} at `{}`", write.bytes, write.path),
+                    None => f.write_str("end of input"),
+                }
+            }
+
+            write!(f, "hash inputs differ at write {}: left wrote ", self.index)?;
+            side(f, &self.left)?;
+            f.write_str(", right wrote ")?;
+            side(f, &self.right)
+        }
+    }
+
+    /// Hash two values with a [`TraceHasher`] and report the first write where they differ.
+    ///
+    /// Writes are compared by their bytes only, so values whose fields were merely renamed
+    /// compare equal. Returns `None` if both values produce identical write streams, in which
+    /// case every [`PortableHasher`] using the default encodings hashes them identically.
+    ///
+    /// # Example
+    /// ```
+    /// use portable_hash::{explain_diff, PortableHash};
+    ///
+    /// #[derive(PortableHash)]
+    /// struct Item {
+    ///     sku: u32,
+    ///     price: u64,
+    /// }
+    ///
+    /// #[derive(PortableHash)]
+    /// struct Order {
+    ///     items: Vec<Item>,
+    /// }
+    ///
+    /// let order = |price| Order {
+    ///     items: (0..5).map(|sku| Item { sku, price: if sku == 3 { price } else { 10 } }).collect(),
+    /// };
+    ///
+    /// let diff = explain_diff(&order(10), &order(12)).unwrap();
+    /// assert_eq!(diff.path(), "items[3].price");
+    /// println!("{}", diff);
+    /// assert!(explain_diff(&order(10), &order(10)).is_none());
+    /// ```
+    pub fn explain_diff<A, B>(a: &A, b: &B) -> Option<HashDiff>
+    where
+        A: PortableHash + ?Sized,
+        B: PortableHash + ?Sized,
+    {
+        let mut left = TraceHasher::new();
+        a.portable_hash(&mut left);
+        let mut right = TraceHasher::new();
+        b.portable_hash(&mut right);
+
+        let mut left = left.into_writes().into_iter();
+        let mut right = right.into_writes().into_iter();
+        let mut index = 0;
+        loop {
+            match (left.next(), right.next()) {
+                (None, None) => return None,
+                (Some(l), Some(r)) if l.bytes == r.bytes => index += 1,
+                (left, right) => return Some(HashDiff { index, left, right }),
+            }
+        }
+    }
+}
@@ -0,0 +1,69 @@
+//! Compare SHA-256 hashing with and without write buffering.
+//!
+//! Run with `cargo bench -p sha-hasher`. Structs made of many small fields make one
+//! `Sha256::update` call per field without buffering, which dominates the hashing cost.
+
+use std::time::{Duration, Instant};
+
+use portable_hash::{BuildPortableHasher, PortableHash, PortableHasher};
+use sha_hasher::{BufferedSha256BuildHasher, Sha256BuildHasher};
+
+/// A record of many small fields, the worst case for unbuffered hashing.
+struct Record {
+    id: u64,
+    flags: [u8; 16],
+    counts: [u16; 16],
+    scores: [i32; 8],
+    enabled: bool,
+}
+
+impl PortableHash for Record {
+    fn portable_hash<H: PortableHasher>(&self, state: &mut H) {
+        self.id.portable_hash(state);
+        for flag in &self.flags {
+            flag.portable_hash(state);
+        }
+        self.counts.portable_hash(state);
+        self.scores.portable_hash(state);
+        self.enabled.portable_hash(state);
+    }
+}
+
+/// Measure the average time to hash `records`, repeated for at least half a second.
+///
+/// Each hash is compared with its entry in `expected`, so that they can't be optimized out.
+fn bench(name: &str, build: impl BuildPortableHasher, records: &[Record], expected: &[u64]) -> Duration {
+    let mut iterations = 0u32;
+    let start = Instant::now();
+    while start.elapsed() < Duration::from_millis(500) {
+        for (record, &hash) in records.iter().zip(expected) {
+            assert_eq!(build.hash_one(record), hash);
+        }
+        iterations += 1;
+    }
+    let per_record = start.elapsed() / (iterations * records.len() as u32);
+    println!("{:<20} {:>10.1?} per record", name, per_record);
+    per_record
+}
+
+fn main() {
+    let records: Vec<Record> = (0..1000)
+        .map(|i| Record {
+            id: i,
+            flags: [i as u8; 16],
+            counts: [i as u16; 16],
+            scores: [i as i32; 8],
+            enabled: i % 2 == 0,
+        })
+        .collect();
+
+    // Buffering must not change the output.
+    let expected: Vec<u64> = records.iter().map(|record| Sha256BuildHasher::default().hash_one(record)).collect();
+    for (record, &hash) in records.iter().zip(&expected) {
+        assert_eq!(BufferedSha256BuildHasher::default().hash_one(record), hash);
+    }
+
+    let unbuffered = bench("sha256", Sha256BuildHasher::default(), &records, &expected);
+    let buffered = bench("sha256 buffered", BufferedSha256BuildHasher::default(), &records, &expected);
+    println!("speedup: {:.2}x", unbuffered.as_secs_f64() / buffered.as_secs_f64());
+}
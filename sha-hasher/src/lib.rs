@@ -1,4 +1,4 @@
-use portable_hash::{DefaultBuildPortableHasher, PortableHasher, PortableHasherOutput};
+use portable_hash::{BufferedBuildHasher, DefaultBuildPortableHasher, PortableHasher, PortableHasherOutput};
 use sha2::Digest;
 
 /// A SHA-256 [`PortableHasher`] implementation.
@@ -10,6 +10,13 @@ pub struct Sha256Hasher {
 /// A SHA-256 [`portable_hash::BuildPortableHasher`] that instantiates a default [`Sha256Hasher`].
 pub type Sha256BuildHasher = DefaultBuildPortableHasher<Sha256Hasher>;
 
+/// A SHA-256 [`portable_hash::BuildPortableHasher`] that collects small writes into 64-byte
+/// blocks, the SHA-256 block size, before passing them to [`Sha256Hasher`].
+///
+/// The output is identical to [`Sha256BuildHasher`], but structs with many small fields hash
+/// faster.
+pub type BufferedSha256BuildHasher = BufferedBuildHasher<Sha256BuildHasher, 64>;
+
 impl PortableHasher for Sha256Hasher {
     fn finish(&self) -> u64 {
         let result = self.hasher.clone().finalize();
@@ -37,7 +44,7 @@ impl PortableHasherOutput<u128> for Sha256Hasher {
 
 #[cfg(test)]
 mod tests {
-    use portable_hash::{BuildPortableHasher, PortableHash, PortableHasher};
+    use portable_hash::{BuildPortableHasher, PortableHash, PortableHasher, PortableSchema};
     use super::*;
 
     /// Test against the portable hasher fixture framework.
@@ -46,6 +53,20 @@ mod tests {
         portable_hash_tester::test_portable_hasher(Sha256BuildHasher::default(), "tests/fixtures.csv");
     }
 
+    /// Snapshot the hashing layout of the derived test types.
+    #[test]
+    fn test_schema_fixtures() {
+        let mut fixtures = portable_hash_tester::FixtureDB::load(Sha256BuildHasher::default(), "tests/schema_fixtures.csv");
+        fixtures.test_schema::<IndexEnum>("schema_index_enum");
+        fixtures.test_schema::<NameEnum>("schema_name_enum");
+        fixtures.test_schema::<ExplicitDiscriminants>("schema_explicit_discriminants");
+        fixtures.test_schema::<WidthU8Enum>("schema_width_u8_enum");
+        fixtures.test_schema::<Node>("schema_node");
+        fixtures.test_schema::<Line>("schema_line");
+        fixtures.test_schema::<Event>("schema_event");
+        fixtures.finish();
+    }
+
     /// Confirm the sha.update() method is bytewise compatible with subsequent calls.
     #[test]
     fn test_sha_understanding() {
@@ -136,6 +157,19 @@ mod tests {
         assert_eq!(hash_one(&NameEnum::UnitB), hash_one(&NameEnumReordered::UnitB));
     }
 
+    #[test]
+    fn test_schema() {
+        assert_eq!(NameEnum::PORTABLE_HASH_SCHEMA, NameEnumReordered::PORTABLE_HASH_SCHEMA, "reordering is safe");
+        assert_ne!(NameEnum::PORTABLE_HASH_SCHEMA, IndexEnum::PORTABLE_HASH_SCHEMA);
+        assert_eq!(
+            WidthU8Enum::PORTABLE_HASH_SCHEMA,
+            "enum discriminant=index width=u8 { 0: (), 1: (), 2: () }",
+        );
+        assert_eq!(Node::PORTABLE_HASH_SCHEMA, "struct (u32, Option<&'a Node<'a>>)");
+        assert_eq!(Line::PORTABLE_HASH_SCHEMA, "struct (with(PointDef) foreign::Point, with(PointDef) foreign::Point)");
+        assert_eq!(Event::PORTABLE_HASH_SCHEMA, "union tag=u8 { 1: KeyEvent, 2: MouseEvent }");
+    }
+
     #[test]
     fn test_name_enum_variants_differ() {
         assert_ne!(hash_one(&NameEnum::UnNamedA(42)), hash_one(&NameEnum::UnNamedB(42)));
@@ -198,6 +232,98 @@ mod tests {
         );
     }
 
+    // ---- Namespace attribute ----
+
+    #[derive(PortableHash)]
+    enum Status {
+        Active(u32),
+    }
+
+    #[derive(PortableHash)]
+    enum Plan {
+        Active(u32),
+    }
+
+    #[derive(PortableHash)]
+    #[portable_hash(namespace)]
+    enum NamespacedStatus {
+        Active(u32),
+    }
+
+    #[derive(PortableHash)]
+    #[portable_hash(namespace = "Plan")]
+    enum NamespacedPlan {
+        #[portable_hash(alias = "Enabled")]
+        Active(u32),
+        Inactive,
+    }
+
+    #[derive(PortableHash)]
+    #[portable_hash(namespace = "NamespacedStatus")]
+    enum ExplicitNamespacedStatus {
+        Active(u32),
+    }
+
+    #[test]
+    fn test_namespace() {
+        // Without a namespace, identically named variants collide across enums.
+        assert_eq!(hash_one(&Status::Active(1)), hash_one(&Plan::Active(1)));
+
+        assert_ne!(hash_one(&NamespacedStatus::Active(1)), hash_one(&Status::Active(1)));
+        assert_ne!(hash_one(&NamespacedStatus::Active(1)), hash_one(&NamespacedPlan::Active(1)));
+        assert_ne!(hash_one(&NamespacedPlan::Active(1)), hash_one(&NamespacedPlan::Inactive));
+
+        // A bare namespace defaults to the type name.
+        assert_eq!(
+            hash_one(&NamespacedStatus::Active(1)),
+            hash_one(&ExplicitNamespacedStatus::Active(1)),
+        );
+    }
+
+    // ---- Aliases and retired variants ----
+
+    #[derive(PortableHash)]
+    #[portable_hash(retired = ["Suspended", 7])]
+    enum EvolvedStatus {
+        #[portable_hash(rename = "Enabled", aliases = ["On", "Live"])]
+        Active(u32),
+        Inactive,
+    }
+
+    #[derive(PortableHash)]
+    enum OriginalStatus {
+        Enabled(u32),
+        Inactive,
+    }
+
+    #[derive(PortableHash)]
+    #[portable_hash(discriminant = "index", retired = [1])]
+    enum RetiredIndex {
+        A,
+        #[portable_hash(discriminant = 2)]
+        C,
+    }
+
+    #[derive(PortableHash)]
+    #[portable_hash(discriminant = "index", discriminant_width = "isize", retired = [-1])]
+    enum RetiredNegative {
+        Below = -2,
+        Zero = 0,
+    }
+
+    #[test]
+    fn test_aliases_and_retired() {
+        // Aliases and retired entries are only checked, they don't change the hash.
+        assert_eq!(hash_one(&EvolvedStatus::Active(5)), hash_one(&OriginalStatus::Enabled(5)));
+        assert_eq!(hash_one(&EvolvedStatus::Inactive), hash_one(&OriginalStatus::Inactive));
+
+        let mut hasher = Sha256Hasher::default();
+        hasher.write_u64(2);
+        assert_eq!(hash_one(&RetiredIndex::C), hasher.finish());
+        assert_ne!(hash_one(&RetiredIndex::A), hash_one(&RetiredIndex::C));
+        assert_ne!(hash_one(&RetiredNegative::Below), hash_one(&RetiredNegative::Zero));
+    }
+
     // ---- Index-mode with manual override ----
 
     #[derive(PortableHash)]
@@ -284,4 +410,1415 @@ mod tests {
         assert_eq!(hash_one(&WidthU8Enum::C), hasher.finish(), "width u8, C=2");
     }
 
+    // ---- Tagged unions ----
+
+    #[derive(Clone, Copy, PortableHash)]
+    #[repr(C)]
+    struct KeyEvent {
+        tag: u8,
+        code: u32,
+    }
+
+    #[derive(Clone, Copy, PortableHash)]
+    #[repr(C)]
+    struct MouseEvent {
+        tag: u8,
+        x: i16,
+        y: i16,
+    }
+
+    #[derive(PortableHash)]
+    #[repr(C)]
+    #[portable_hash(union_tag = "tag", variants(key = 1, mouse = 2))]
+    union Event {
+        tag: u8,
+        key: KeyEvent,
+        mouse: MouseEvent,
+    }
+
+    // SAFETY: every `Event` in these tests sets `tag` through a member that starts with it, and
+    // sets the member matching tags 1 and 2.
+    unsafe impl portable_hash::PortableHashUnion for Event {}
+
+    #[test]
+    fn test_tagged_union() {
+        let key = KeyEvent { tag: 1, code: 42 };
+        assert_eq!(hash_one(&Event { key }), hash_one(&(1u8, key)));
+
+        let mouse = MouseEvent { tag: 2, x: -3, y: 4 };
+        assert_eq!(hash_one(&Event { mouse }), hash_one(&(2u8, mouse)));
+    }
+
+    /// Tag values not listed in `variants(...)` hash only the tag.
+    #[test]
+    fn test_tagged_union_unknown_tag() {
+        assert_eq!(hash_one(&Event { tag: 9 }), hash_one(&9u8));
+    }
+
+    // ---- Trait bound inference ----
+
+    /// Recursive types must not put `Option<&Node>: PortableHash` in their own where-clause.
+    #[derive(PortableHash)]
+    struct Node<'a> {
+        value: u32,
+        next: Option<&'a Node<'a>>,
+    }
+
+    #[test]
+    fn test_recursive_struct() {
+        let tail = Node { value: 2, next: None };
+        let head = Node { value: 1, next: Some(&tail) };
+
+        let mut hasher = Sha256Hasher::default();
+        hasher.write_u32(1);
+        hasher.write_u8(1);
+        hasher.write_u32(2);
+        hasher.write_u8(0);
+        assert_eq!(hash_one(&head), hasher.finish());
+    }
+
+    struct NotHashable;
+
+    /// `T` only appears in `PhantomData`, so it shouldn't require `T: PortableHash`.
+    #[derive(PortableHash)]
+    struct Tagged<T> {
+        id: u64,
+        _marker: core::marker::PhantomData<T>,
+    }
+
+    #[test]
+    fn test_phantom_data_param_unbounded() {
+        let tagged = Tagged::<NotHashable> { id: 7, _marker: core::marker::PhantomData };
+        assert_eq!(hash_one(&tagged), hash_one(&7u64));
+    }
+
+    trait Keyed {
+        type Key;
+    }
+
+    impl Keyed for NotHashable {
+        type Key = u32;
+    }
+
+    /// Associated types are bounded by the field type, not by `T`.
+    #[derive(PortableHash)]
+    enum KeyedEnum<T: Keyed> {
+        Key(T::Key),
+        Qualified(<T as Keyed>::Key),
+        Empty,
+    }
+
+    #[test]
+    fn test_associated_type_bound() {
+        assert_ne!(hash_one(&KeyedEnum::<NotHashable>::Key(3)), hash_one(&KeyedEnum::<NotHashable>::Empty));
+        assert_ne!(hash_one(&KeyedEnum::<NotHashable>::Key(3)), hash_one(&KeyedEnum::<NotHashable>::Qualified(3)));
+    }
+
+    /// The container bound replaces the inferred `T: PortableHash` bound.
+    #[derive(PortableHash)]
+    #[portable_hash(bound = "T: PortableHash + Copy")]
+    struct ContainerBound<T> {
+        value: T,
+    }
+
+    /// The field bound replaces the bound inferred from `value`, so `T` is unbounded.
+    #[derive(PortableHash)]
+    struct FieldBound<T: Keyed> {
+        #[portable_hash(bound = "T::Key: PortableHash")]
+        value: T::Key,
+        _marker: core::marker::PhantomData<T>,
+    }
+
+    #[test]
+    fn test_bound_overrides() {
+        assert_eq!(hash_one(&ContainerBound { value: 5u8 }), hash_one(&5u8));
+
+        let field_bound = FieldBound::<NotHashable> { value: 9, _marker: core::marker::PhantomData };
+        assert_eq!(hash_one(&field_bound), hash_one(&9u32));
+    }
+
+    // ---- Remote derive ----
+
+    /// Stand-in for a foreign crate whose types don't implement `PortableHash`.
+    mod foreign {
+        pub struct Point {
+            pub x: i32,
+            pub y: i32,
+        }
+
+        pub enum Either<L, R> {
+            Left(L),
+            Right { value: R },
+        }
+    }
+
+    #[derive(PortableHash)]
+    #[portable_hash(remote = "foreign::Point")]
+    #[allow(dead_code)]
+    struct PointDef {
+        x: i32,
+        y: i32,
+    }
+
+    #[derive(PortableHash)]
+    #[portable_hash(remote = "foreign::Either<L, R>")]
+    #[allow(dead_code)]
+    enum EitherDef<L, R> {
+        Left(L),
+        Right { value: R },
+    }
+
+    #[derive(PortableHash)]
+    #[allow(dead_code)]
+    enum LocalEither<L, R> {
+        Left(L),
+        Right { value: R },
+    }
+
+    #[derive(PortableHash)]
+    struct Line {
+        #[portable_hash(with = "PointDef")]
+        start: foreign::Point,
+        #[portable_hash(with = "PointDef")]
+        end: foreign::Point,
+    }
+
+    #[test]
+    fn test_remote_derive() {
+        let point = foreign::Point { x: 1, y: -2 };
+        assert_eq!(hash_one(&portable_hash::Remote::<PointDef, _>::new(&point)), hash_one(&(1i32, -2i32)));
+
+        let line = Line { start: foreign::Point { x: 1, y: 2 }, end: foreign::Point { x: 3, y: 4 } };
+        assert_eq!(hash_one(&line), hash_one(&(1i32, 2i32, 3i32, 4i32)));
+
+        let left = foreign::Either::<u8, u16>::Left(5);
+        let right = foreign::Either::<u8, u16>::Right { value: 6 };
+        assert_eq!(
+            hash_one(&portable_hash::Remote::<EitherDef<u8, u16>, _>::new(&left)),
+            hash_one(&LocalEither::<u8, u16>::Left(5)),
+        );
+        assert_eq!(
+            hash_one(&portable_hash::Remote::<EitherDef<u8, u16>, _>::new(&right)),
+            hash_one(&LocalEither::<u8, u16>::Right { value: 6 }),
+        );
+    }
+
+    // ---- impl_portable_hash! ----
+
+    struct UserId(u64);
+    struct Labelled<T> {
+        label: u8,
+        value: T,
+    }
+
+    portable_hash::impl_portable_hash!(UserId { 0 });
+    portable_hash::impl_portable_hash!(<T> Labelled<T> { label, value });
+
+    #[test]
+    fn test_impl_portable_hash_macro() {
+        assert_eq!(hash_one(&UserId(7)), hash_one(&7u64));
+        assert_eq!(hash_one(&Labelled { label: 1, value: 2u32 }), hash_one(&(1u8, 2u32)));
+    }
+
+    // ---- Crate path override ----
+
+    /// Stand-in for a façade crate that re-exports portable_hash.
+    mod facade {
+        pub use portable_hash as hash;
+    }
+
+    #[derive(facade::hash::PortableHash)]
+    #[portable_hash(crate = "crate::tests::facade::hash")]
+    struct ReexportedStruct {
+        a: u32,
+    }
+
+    #[test]
+    fn test_crate_path_override() {
+        assert_eq!(hash_one(&ReexportedStruct { a: 3 }), hash_one(&3u32));
+    }
+
+    // ---- Name-mode discriminant widths ----
+
+    #[derive(PortableHash)]
+    enum NameU64 {
+        Pending,
+    }
+
+    #[derive(PortableHash)]
+    #[portable_hash(discriminant_width = "u8")]
+    enum NameU8 {
+        Pending,
+        Paid(u32),
+    }
+
+    #[derive(PortableHash)]
+    #[portable_hash(discriminant_width = "u32")]
+    enum NameU32 {
+        Pending,
+    }
+
+    #[derive(PortableHash)]
+    #[portable_hash(discriminant_width = "isize")]
+    enum NameIsize {
+        Pending,
+    }
+
+    #[test]
+    fn test_name_discriminant_widths() {
+        let pending = 0x7863_6B92_C1A3_909Cu64; // FNV-1a 64 of "Pending"
+        assert_eq!(hash_one(&NameU64::Pending), hash_one(&pending));
+
+        let fold32 = (pending >> 32) ^ (pending & 0xffff_ffff);
+        let fold16 = (fold32 >> 16) ^ (fold32 & 0xffff);
+        let fold8 = (fold16 >> 8) ^ (fold16 & 0xff);
+        assert_eq!(fold8, 140);
+
+        assert_eq!(hash_one(&NameU8::Pending), hash_one(&(fold8 as u8)));
+        assert_eq!(hash_one(&NameU8::Paid(5)), hash_one(&(101u8, 5u32)));
+        assert_eq!(hash_one(&NameU32::Pending), hash_one(&(fold32 as u32)));
+        assert_eq!(hash_one(&NameIsize::Pending), hash_one(&(pending as i64)));
+    }
+
+    // ---- Skipped fields ----
+
+    #[derive(PortableHash)]
+    struct Cached {
+        value: u32,
+        #[portable_hash(skip)]
+        #[allow(dead_code)]
+        cache: NotHashable,
+    }
+
+    #[derive(PortableHash)]
+    enum CachedEnum {
+        Named {
+            value: u32,
+            #[portable_hash(skip)]
+            #[allow(dead_code)]
+            cache: NotHashable,
+        },
+        Unnamed(#[portable_hash(skip)] NotHashable, u32),
+    }
+
+    #[derive(PortableHash)]
+    enum PlainEnum {
+        Named { value: u32 },
+        Unnamed(u32),
+    }
+
+    #[test]
+    fn test_skip() {
+        assert_eq!(hash_one(&Cached { value: 3, cache: NotHashable }), hash_one(&3u32));
+        assert_eq!(
+            hash_one(&CachedEnum::Named { value: 3, cache: NotHashable }),
+            hash_one(&PlainEnum::Named { value: 3 }),
+        );
+        assert_eq!(
+            hash_one(&CachedEnum::Unnamed(NotHashable, 3)),
+            hash_one(&PlainEnum::Unnamed(3)),
+        );
+        assert_eq!(Cached::PORTABLE_HASH_SCHEMA, "struct (u32)");
+    }
+
+    // ---- Transparent newtypes ----
+
+    #[derive(PortableHash)]
+    #[repr(transparent)]
+    #[portable_hash(transparent)]
+    struct UserIdNewtype(u64);
+
+    #[derive(PortableHash)]
+    #[repr(transparent)]
+    #[portable_hash(transparent)]
+    struct Byte {
+        value: u8,
+        #[portable_hash(skip)]
+        unit: core::marker::PhantomData<NotHashable>,
+    }
+
+    #[derive(PortableHash)]
+    #[repr(transparent)]
+    #[portable_hash(transparent)]
+    struct Transparent<T>(T);
+
+    #[test]
+    fn test_transparent() {
+        assert_eq!(hash_one(&UserIdNewtype(7)), hash_one(&7u64));
+        assert_eq!(hash_one(&[UserIdNewtype(1), UserIdNewtype(2)]), hash_one(&[1u64, 2]));
+
+        // Slices delegate to `u8`'s `write_bytes` override, rather than hashing each byte.
+        let bytes = [1u8, 2, 3].map(|value| Byte { value, unit: core::marker::PhantomData });
+        assert_eq!(hash_one(&bytes), hash_one(&[1u8, 2, 3]));
+
+        assert_eq!(hash_one(&[Transparent(4u8), Transparent(5)]), hash_one(&[4u8, 5]));
+        assert_eq!(hash_one(&Transparent("name")), hash_one(&"name"));
+
+        assert_eq!(UserIdNewtype::PORTABLE_HASH_SCHEMA, "transparent u64");
+        assert_eq!(Transparent::<u8>::PORTABLE_HASH_SCHEMA, "transparent T");
+    }
+
+    // ---- Field encodings ----
+
+    #[derive(PortableHash)]
+    struct WireHeader {
+        #[portable_hash(encoding = "varint")]
+        len: u32,
+        #[portable_hash(encoding = "be")]
+        kind: u16,
+        #[portable_hash(encoding = "u8")]
+        flag: bool,
+        #[portable_hash(encoding = "u64")]
+        seq: u32,
+    }
+
+    #[derive(PortableHash)]
+    #[portable_hash(discriminant = "index", discriminant_width = "u8")]
+    enum WireMessage {
+        Ping(#[portable_hash(encoding = "varint")] u64),
+    }
+
+    fn hash_bytes(bytes: &[u8]) -> u64 {
+        let mut hasher = Sha256Hasher::default();
+        hasher.write(bytes);
+        hasher.finish()
+    }
+
+    #[test]
+    fn test_field_encodings() {
+        let header = WireHeader { len: 300, kind: 0x0102, flag: true, seq: 7 };
+        assert_eq!(
+            hash_one(&header),
+            hash_bytes(&[0xac, 0x02, 0x01, 0x02, 0x01, 7, 0, 0, 0, 0, 0, 0, 0]),
+        );
+
+        assert_eq!(
+            hash_one(&WireMessage::Ping(u64::MAX)),
+            hash_bytes(&[0, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01]),
+        );
+
+        assert_eq!(
+            WireHeader::PORTABLE_HASH_SCHEMA,
+            "struct (encoding(varint) u32, encoding(be) u16, encoding(u8) bool, encoding(u64) u32)",
+        );
+    }
+
+    // ---- Fixed-length arrays ----
+
+    #[derive(PortableHash)]
+    struct PrefixedBlock<const N: usize> {
+        data: [u16; N],
+    }
+
+    #[derive(PortableHash)]
+    struct FixedBlock<const N: usize> {
+        #[portable_hash(encoding = "fixed_len")]
+        data: [u16; N],
+    }
+
+    #[derive(PortableHash)]
+    struct FixedGeneric<T> {
+        #[portable_hash(encoding = "fixed_len")]
+        items: [T; 2],
+    }
+
+    #[derive(PortableHash)]
+    struct FixedDigest {
+        #[portable_hash(encoding = "fixed_len")]
+        digest: [u8; 4],
+    }
+
+    /// Snapshot hashes with and without the array length prefix.
+    #[test]
+    fn test_fixed_len_fixtures() {
+        let mut fixtures = portable_hash_tester::FixtureDB::load(Sha256BuildHasher::default(), "tests/encoding_fixtures.csv");
+        fixtures.test_fixture_no_debug("block_prefixed_u16_3", PrefixedBlock { data: [1, 2, 3] });
+        fixtures.test_fixture_no_debug("block_fixed_len_u16_3", FixedBlock { data: [1, 2, 3] });
+        fixtures.test_fixture_no_debug("block_prefixed_u16_0", PrefixedBlock { data: [] });
+        fixtures.test_fixture_no_debug("block_fixed_len_u16_0", FixedBlock { data: [] });
+        fixtures.finish();
+    }
+
+    #[test]
+    fn test_fixed_len() {
+        // The only difference is the 8-byte length prefix.
+        assert_eq!(
+            hash_one(&PrefixedBlock { data: [1, 2, 3] }),
+            hash_bytes(&[3, 0, 0, 0, 0, 0, 0, 0, 1, 0, 2, 0, 3, 0]),
+        );
+        assert_eq!(hash_one(&FixedBlock { data: [1, 2, 3] }), hash_bytes(&[1, 0, 2, 0, 3, 0]));
+        assert_eq!(hash_one(&FixedBlock::<0> { data: [] }), hash_bytes(&[]));
+
+        assert_eq!(hash_one(&FixedGeneric { items: ["a", "b"] }), hash_one(&("a", "b")));
+        assert_eq!(FixedBlock::<3>::PORTABLE_HASH_SCHEMA, "struct (encoding(fixed_len) [u16; N])");
+
+        // Byte arrays keep a single write, through `u8`'s `portable_hash_slice_unprefixed`.
+        let mut counter = portable_hash::CountingHasher::new();
+        FixedDigest { digest: [1, 2, 3, 4] }.portable_hash(&mut counter);
+        assert_eq!((counter.stats().write, counter.stats().calls()), (1, 1));
+        assert_eq!(hash_one(&FixedDigest { digest: [1, 2, 3, 4] }), hash_bytes(&[1, 2, 3, 4]));
+        assert_eq!(
+            hash_one(&FixedGeneric { items: [Transparent(1u8), Transparent(2)] }),
+            hash_bytes(&[1, 2]),
+        );
+    }
+
+    // ---- Default-valued field elision ----
+
+    /// The definitions before and after a field was added, as if from two releases.
+    mod v1 {
+        use portable_hash::PortableHash;
+
+        #[derive(Debug, PortableHash)]
+        pub struct Order {
+            pub id: u64,
+            pub quantity: u32,
+            #[portable_hash(skip_if_default)]
+            pub discount: Option<u32>,
+        }
+
+        #[derive(Debug, PortableHash)]
+        pub struct Receipt {
+            pub total: u64,
+        }
+
+        #[derive(Debug, Default, PartialEq, PortableHash)]
+        #[portable_hash(evolvable)]
+        pub struct Settings {
+            pub volume: u8,
+            pub muted: bool,
+        }
+    }
+
+    mod v2 {
+        use portable_hash::PortableHash;
+
+        #[derive(Debug, PortableHash)]
+        pub struct Order {
+            pub id: u64,
+            pub quantity: u32,
+            #[portable_hash(skip_if_default)]
+            pub discount: Option<u32>,
+            #[portable_hash(skip_if_default)]
+            pub gift: bool,
+        }
+
+        #[derive(Debug, PortableHash)]
+        pub struct Receipt {
+            pub total: u64,
+            #[portable_hash(skip_if_default)]
+            pub refunded: bool,
+        }
+
+        #[derive(Debug, Default, PartialEq, PortableHash)]
+        #[portable_hash(evolvable)]
+        pub struct Settings {
+            pub volume: u8,
+            pub theme: u32,
+            pub muted: bool,
+        }
+    }
+
+    #[derive(PortableHash)]
+    #[portable_hash(evolvable)]
+    struct EvolvableGeneric<T> {
+        value: T,
+    }
+
+    #[test]
+    fn test_skip_if_default() {
+        let old = v1::Order { id: 1, quantity: 2, discount: None };
+        let new = v2::Order { id: 1, quantity: 2, discount: None, gift: false };
+        assert_eq!(hash_one(&old), hash_one(&new));
+
+        // Non-default values are written after the FNV-1a 64 hash of the field name. Adding the
+        // first `skip_if_default` field to a plain struct keeps its hashes too.
+        assert_eq!(hash_one(&old), hash_one(&(1u64, 2u32)));
+        let discounted = v2::Order { id: 1, quantity: 2, discount: Some(5), gift: false };
+        assert_eq!(hash_one(&discounted), hash_one(&(1u64, 2u32, 0xC02A_F778_5933_4F1Eu64, Some(5u32))));
+        assert_eq!(hash_one(&v1::Receipt { total: 9 }), hash_one(&v2::Receipt { total: 9, refunded: false }));
+
+        // `evolvable` fields end with the FNV-1a 64 hash of an empty name.
+        let end = 0xCBF2_9CE4_8422_2325u64;
+
+        let settings = v2::Settings { volume: 0, theme: 3, muted: true };
+        assert_eq!(hash_one(&settings), hash_one(&(0xEAF8_8E72_5DBA_94B2u64, 3u32, 0xD60F_4B3F_93FF_6B70u64, true, end)));
+        assert_eq!(hash_one(&v2::Settings::default()), hash_one(&end));
+
+        // The end tag keeps a default value, which writes no fields, from moving between elements.
+        let themed = v2::Settings { theme: 3, ..Default::default() };
+        assert_ne!(
+            hash_one(&[themed, v2::Settings::default()]),
+            hash_one(&[v2::Settings::default(), v2::Settings { theme: 3, ..Default::default() }]),
+        );
+
+        assert_eq!(hash_one(&EvolvableGeneric { value: 0u32 }), hash_one(&EvolvableGeneric { value: 0u64 }));
+        assert_ne!(hash_one(&EvolvableGeneric { value: 1u32 }), hash_one(&EvolvableGeneric { value: 0u32 }));
+
+        assert_eq!(
+            v2::Order::PORTABLE_HASH_SCHEMA,
+            "struct (u64, u32, skip_if_default(discount) Option<u32>, skip_if_default(gift) bool)",
+        );
+        assert_eq!(
+            v2::Settings::PORTABLE_HASH_SCHEMA,
+            "evolvable struct (skip_if_default(volume) u8, skip_if_default(theme) u32, skip_if_default(muted) bool)",
+        );
+    }
+
+    /// Fixtures recorded with the old definitions must still pass with the new definitions, as
+    /// long as the added fields hold their defaults.
+    #[test]
+    fn test_evolution_fixtures() {
+        let path = "tests/evolution_fixtures.csv";
+
+        let mut fixtures = portable_hash_tester::FixtureDB::load(Sha256BuildHasher::default(), path);
+        fixtures.test_fixture("order_1_2", v1::Order { id: 1, quantity: 2, discount: None });
+        fixtures.test_fixture("order_max_0", v1::Order { id: u64::MAX, quantity: 0, discount: None });
+        fixtures.test_fixture("receipt_0", v1::Receipt { total: 0 });
+        fixtures.test_fixture("receipt_1250", v1::Receipt { total: 1250 });
+        fixtures.test_fixture("settings_7_unmuted", v1::Settings { volume: 7, muted: false });
+        fixtures.test_fixture("settings_0_muted", v1::Settings { volume: 0, muted: true });
+        // A default value writes only the end tag, so these must differ.
+        let changed = || v1::Settings { volume: 7, muted: false };
+        fixtures.test_fixture("settings_pair_changed_default", [changed(), v1::Settings::default()]);
+        fixtures.test_fixture("settings_pair_default_changed", [v1::Settings::default(), changed()]);
+        fixtures.finish();
+
+        let mut fixtures = portable_hash_tester::FixtureDB::load(Sha256BuildHasher::default(), path);
+        fixtures.update_fixtures(false);
+        fixtures.test_fixture("order_1_2", v2::Order { id: 1, quantity: 2, discount: None, gift: false });
+        fixtures.test_fixture("order_max_0", v2::Order { id: u64::MAX, quantity: 0, discount: None, gift: false });
+        fixtures.test_fixture("receipt_0", v2::Receipt { total: 0, refunded: false });
+        fixtures.test_fixture("receipt_1250", v2::Receipt { total: 1250, refunded: false });
+        fixtures.test_fixture("settings_7_unmuted", v2::Settings { volume: 7, theme: 0, muted: false });
+        fixtures.test_fixture("settings_0_muted", v2::Settings { volume: 0, theme: 0, muted: true });
+        let changed = || v2::Settings { volume: 7, theme: 0, muted: false };
+        fixtures.test_fixture("settings_pair_changed_default", [changed(), v2::Settings::default()]);
+        fixtures.test_fixture("settings_pair_default_changed", [v2::Settings::default(), changed()]);
+        fixtures.finish();
+    }
+
+    // ---- Trait objects ----
+
+    /// Records the `PortableHasher` methods that reach it, to check that overridden methods are
+    /// still called through `dyn DynPortableHasher`.
+    #[derive(Default)]
+    struct CallLog(Vec<String>);
+
+    impl PortableHasher for CallLog {
+        fn finish(&self) -> u64 {
+            self.0.len() as u64
+        }
+
+        fn write(&mut self, bytes: &[u8]) {
+            self.0.push(format!("write {:?}", bytes));
+        }
+
+        fn write_str(&mut self, s: &str) {
+            self.0.push(format!("write_str {:?}", s));
+        }
+
+        fn write_bytes(&mut self, bytes: &[u8]) {
+            self.0.push(format!("write_bytes {:?}", bytes));
+        }
+
+        fn write_short<const LEN: usize>(&mut self, bytes: [u8; LEN]) {
+            self.0.push(format!("write_short {:?}", bytes));
+        }
+    }
+
+    #[derive(PortableHash)]
+    struct Mixed<'a> {
+        name: &'a str,
+        data: &'a [u8],
+        id: u128,
+        flags: (i8, u16),
+    }
+
+    #[test]
+    fn test_dyn_portable_hash() {
+        use portable_hash::{DynPortableHash, DynPortableHasher};
+
+        let mixed = Mixed { name: "dyn", data: &[1, 2, 3], id: 7, flags: (-1, 2) };
+
+        let mut direct = CallLog::default();
+        mixed.portable_hash(&mut direct);
+        let mut erased = CallLog::default();
+        mixed.dyn_portable_hash(&mut erased as &mut dyn DynPortableHasher);
+        assert_eq!(direct.0, erased.0);
+        assert!(direct.0.contains(&"write_str \"dyn\"".to_string()));
+
+        let boxed: Box<dyn DynPortableHash> = Box::new(Mixed { name: "dyn", data: &[1, 2, 3], id: 7, flags: (-1, 2) });
+        let mut through_box = CallLog::default();
+        boxed.portable_hash(&mut through_box);
+        assert_eq!(direct.0, through_box.0);
+
+        let values: [&(dyn DynPortableHash + Send + Sync); 3] = [&1u32, &"two", &[3u8, 4]];
+        assert_eq!(hash_one(&values), hash_one(&(3usize, 1u32, "two", [3u8, 4])));
+    }
+
+    #[cfg(feature = "alloc")]
+    mod registry {
+        use portable_hash::{BoxedBuildPortableHasher, BuildPortableHasher, HasherRegistry};
+
+        use crate::Sha256BuildHasher;
+
+        fn registry() -> HasherRegistry {
+            let mut registry = HasherRegistry::new();
+            registry.register("sha256", BoxedBuildPortableHasher::with_output::<_, [u8; 32]>(Sha256BuildHasher::default()));
+            registry.register("sha256-u64", BoxedBuildPortableHasher::new(Sha256BuildHasher::default()));
+            registry
+        }
+
+        /// The boxed hasher must pass the same fixtures as the static hasher.
+        #[test]
+        fn test_registry_fixtures() {
+            let builder = registry().remove("sha256").unwrap();
+            portable_hash_tester::test_portable_hasher(builder, "tests/fixtures.csv");
+        }
+
+        #[test]
+        fn test_registry_output() {
+            let registry = registry();
+            assert_eq!(registry.names().collect::<Vec<_>>(), ["sha256", "sha256-u64"]);
+            assert!(registry.get("blake3").is_none());
+
+            let value = ("order", 42u64, vec![1u8, 2, 3]);
+            let expected: [u8; 32] = Sha256BuildHasher::default().finalize_one(&value);
+
+            let sha256 = registry.get("sha256").unwrap();
+            let digest: Vec<u8> = sha256.finalize_one(&value);
+            assert_eq!(digest, expected);
+            assert_eq!(sha256.hash_one(&value), Sha256BuildHasher::default().hash_one(&value));
+
+            let sha256_u64 = registry.get("sha256-u64").unwrap();
+            let digest: Vec<u8> = sha256_u64.finalize_one(&value);
+            assert_eq!(digest, Sha256BuildHasher::default().hash_one(&value).to_le_bytes());
+        }
+    }
+
+    // ---- Tee hashers ----
+
+    #[test]
+    fn test_tee_hasher() {
+        use portable_hash::{DefaultBuildPortableHasher, TeeBuildHasher, TeeBuildHasher3, TeeHasher};
+
+        let mixed = Mixed { name: "tee", data: &[4, 5], id: 9, flags: (3, 4) };
+
+        // Each hasher sees exactly the calls it would see on its own, including overrides.
+        let mut single = CallLog::default();
+        mixed.portable_hash(&mut single);
+        let mut tee = TeeHasher(CallLog::default(), CallLog::default());
+        mixed.portable_hash(&mut tee);
+        assert_eq!(tee.0 .0, single.0);
+        assert_eq!(tee.1 .0, single.0);
+
+        let build = TeeBuildHasher(Sha256BuildHasher::default(), DefaultBuildPortableHasher::<CallLog>::default());
+        assert_eq!(build.hash_one(&mixed), hash_one(&mixed));
+
+        let build = TeeBuildHasher3(Sha256BuildHasher::default(), Sha256BuildHasher::default(), Sha256BuildHasher::default());
+        let (digest, short, long): ([u8; 32], u128, [u8; 32]) = build.finalize_one(&mixed);
+        let expected: [u8; 32] = Sha256BuildHasher::default().finalize_one(&mixed);
+        assert_eq!(digest, expected);
+        assert_eq!(long, expected);
+        assert_eq!(short, Sha256BuildHasher::default().finalize_one::<u128, _>(&mixed));
+    }
+
+    /// The first hasher's `finish` must pass the same fixtures as the hasher on its own.
+    #[test]
+    fn test_tee_hasher_fixtures() {
+        use portable_hash::{DefaultBuildPortableHasher, TeeBuildHasher};
+
+        let build = TeeBuildHasher(Sha256BuildHasher::default(), DefaultBuildPortableHasher::<CallLog>::default());
+        portable_hash_tester::test_portable_hasher(build, "tests/fixtures.csv");
+    }
+
+    // ---- Buffered hashers ----
+
+    /// The buffered hasher must pass the same fixtures as the unbuffered hasher.
+    #[test]
+    fn test_buffered_fixtures() {
+        portable_hash_tester::test_portable_hasher(BufferedSha256BuildHasher::default(), "tests/fixtures.csv");
+        let mut fixtures = portable_hash_tester::FixtureDB::load(BufferedSha256BuildHasher::default(), "tests/encoding_fixtures.csv");
+        fixtures.test_fixture_no_debug("block_prefixed_u16_3", PrefixedBlock { data: [1, 2, 3] });
+        fixtures.test_fixture_no_debug("block_fixed_len_u16_3", FixedBlock { data: [1, 2, 3] });
+        fixtures.test_fixture_no_debug("block_prefixed_u16_0", PrefixedBlock { data: [] });
+        fixtures.test_fixture_no_debug("block_fixed_len_u16_0", FixedBlock { data: [] });
+        fixtures.finish();
+    }
+
+    /// Hash with an `N`-byte buffer, checking every output against the unbuffered hasher.
+    fn check_buffered<const N: usize, T: PortableHash>(value: &T) {
+        let build = portable_hash::BufferedBuildHasher::<Sha256BuildHasher, N>::default();
+        let buffered: [u8; 32] = build.finalize_one(value);
+        let expected: [u8; 32] = Sha256BuildHasher::default().finalize_one(value);
+        assert_eq!(buffered, expected, "buffer size {}", N);
+        assert_eq!(build.hash_one(value), hash_one(value), "buffer size {}", N);
+        assert_eq!(build.finalize_one::<u128, _>(value), Sha256BuildHasher::default().finalize_one::<u128, _>(value));
+    }
+
+    #[test]
+    fn test_buffered_sizes() {
+        let long = [7u8; 200];
+        let values = (
+            Mixed { name: "buffered", data: &long, id: u128::MAX, flags: (-5, 6) },
+            [1u64, 2, 3, 4, 5, 6, 7, 8, 9],
+            "a string longer than some of the buffers",
+            (0u8, 1u16, 2u32),
+        );
+        check_buffered::<0, _>(&values);
+        check_buffered::<1, _>(&values);
+        check_buffered::<7, _>(&values);
+        check_buffered::<16, _>(&values);
+        check_buffered::<64, _>(&values);
+        check_buffered::<256, _>(&values);
+        check_buffered::<64, _>(&());
+
+        // Flushing hands the buffered bytes to the wrapped hasher.
+        let mut hasher = portable_hash::BufferedHasher::<Sha256Hasher, 64>::default();
+        values.portable_hash(&mut hasher);
+        let inner = hasher.into_inner();
+        assert_eq!(inner.finish(), hash_one(&values));
+    }
+
+    // ---- Write statistics ----
+
+    #[test]
+    fn test_counting_hasher() {
+        use portable_hash::{CountingHasher, DefaultBuildPortableHasher, HashStats, TeeHasher};
+
+        let mixed = Mixed { name: "count", data: &[1, 2, 3], id: 7, flags: (-1, 2) };
+        let expected = HashStats {
+            bytes: (8 + 5) + (8 + 3) + 16 + 1 + 2,
+            write_u128: 1,
+            write_i8: 1,
+            write_u16: 1,
+            write_str: 1,
+            write_bytes: 1,
+            ..HashStats::default()
+        };
+
+        let build = DefaultBuildPortableHasher::<CountingHasher>::default();
+        assert_eq!(build.finalize_one::<HashStats, _>(&mixed), expected);
+        assert_eq!(expected.calls(), 5);
+        assert_eq!(build.hash_one(&mixed), 0);
+
+        // Wrapping forwards every call, so the wrapped hasher's output is unchanged.
+        let mut counter = CountingHasher::wrap(Sha256Hasher::default());
+        mixed.portable_hash(&mut counter);
+        assert_eq!(*counter.stats(), expected);
+        assert_eq!(counter.finish(), hash_one(&mixed));
+        assert_eq!(counter.into_inner().finish(), hash_one(&mixed));
+
+        let mut tee = TeeHasher(CountingHasher::new(), Sha256Hasher::default());
+        mixed.portable_hash(&mut tee);
+        let (stats, digest): (HashStats, [u8; 32]) = tee.finalize();
+        assert_eq!(stats, expected);
+        assert_eq!(digest, Sha256BuildHasher::default().finalize_one::<[u8; 32], _>(&mixed));
+
+        // `bytes` is the length of the default encoding.
+        let values = ([1u64, 2, 3], "abc", (4usize, -5isize), [6u8; 40]);
+        let mut counter = CountingHasher::new();
+        values.portable_hash(&mut counter);
+        // A buffered hasher passes the default encodings to `write`.
+        let mut raw = portable_hash::BufferedHasher::<CountingHasher, 0>::default();
+        values.portable_hash(&mut raw);
+        assert_eq!(counter.stats().bytes, raw.into_inner().stats().bytes);
+        assert_eq!(counter.stats().bytes, (8 + 3 * 8) + (8 + 3) + 8 + 8 + (8 + 40));
+    }
+
+    // ---- Resource limits ----
+
+    #[derive(PortableHash)]
+    enum Nested<'a> {
+        Leaf,
+        Node(&'a Nested<'a>),
+        Erased(&'a dyn portable_hash::DynPortableHash),
+    }
+
+    #[test]
+    fn test_limited_hasher() {
+        use portable_hash::{BufferedHasher, CountingHasher, HashError, HashLimits, LimitedBuildHasher, LimitedHasher, TeeHasher};
+
+        let mixed = Mixed { name: "limited", data: &[1, 2, 3], id: 7, flags: (-1, 2) };
+        // 8 + 7 for the name, 8 + 3 for the data, 16 + 1 + 2 for the integers, in 5 calls, at a
+        // depth of 1.
+        let exact = HashLimits { max_bytes: 45, max_len: 7, max_calls: 5, max_depth: 1 };
+
+        assert_eq!(Sha256BuildHasher::default().try_hash_one(&mixed), Ok(hash_one(&mixed)));
+        let build = LimitedBuildHasher::new(Sha256BuildHasher::default(), exact);
+        assert_eq!(build.try_hash_one(&mixed), Ok(hash_one(&mixed)));
+
+        let over_bytes = LimitedBuildHasher::new(Sha256BuildHasher::default(), HashLimits { max_bytes: 44, ..exact });
+        assert_eq!(over_bytes.try_hash_one(&mixed), Err(HashError::ByteBudgetExceeded { max_bytes: 44 }));
+        let over_len = LimitedBuildHasher::new(Sha256BuildHasher::default(), HashLimits { max_len: 6, ..exact });
+        assert_eq!(over_len.try_hash_one(&mixed), Err(HashError::LengthTooLarge { len: 7, max_len: 6 }));
+        let over_calls = LimitedBuildHasher::new(Sha256BuildHasher::default(), HashLimits { max_calls: 4, ..exact });
+        assert_eq!(over_calls.try_hash_one(&mixed), Err(HashError::CallLimitExceeded { max_calls: 4 }));
+        let over_depth = LimitedBuildHasher::new(Sha256BuildHasher::default(), HashLimits { max_depth: 0, ..exact });
+        assert_eq!(over_depth.try_hash_one(&mixed), Err(HashError::DepthExceeded { max_depth: 0 }));
+
+        // Each level of nesting is a variant and its field, and the `Leaf` variant is at depth 5.
+        let tree = Nested::Node(&Nested::Node(&Nested::Leaf));
+        let depth = |max_depth| HashLimits { max_depth, ..HashLimits::default() };
+        let build = LimitedBuildHasher::new(Sha256BuildHasher::default(), depth(5));
+        assert_eq!(build.try_hash_one(&tree), Ok(hash_one(&tree)));
+        let build = LimitedBuildHasher::new(Sha256BuildHasher::default(), depth(4));
+        assert_eq!(build.try_hash_one(&tree), Err(HashError::DepthExceeded { max_depth: 4 }));
+        // Levels inside a trait object count too.
+        let erased = Nested::Erased(&Nested::Node(&Nested::Leaf));
+        let build = LimitedBuildHasher::new(Sha256BuildHasher::default(), depth(5));
+        assert_eq!(build.try_hash_one(&erased), Ok(hash_one(&erased)));
+        let build = LimitedBuildHasher::new(Sha256BuildHasher::default(), depth(4));
+        assert_eq!(build.try_hash_one(&erased), Err(HashError::DepthExceeded { max_depth: 4 }));
+        // Slice elements add a level too.
+        let build = LimitedBuildHasher::new(Sha256BuildHasher::default(), depth(0));
+        assert_eq!(build.try_hash_one(&[1u32, 2]), Err(HashError::DepthExceeded { max_depth: 0 }));
+
+        // Collections are limited by their element count, and nothing is forwarded after the
+        // first error.
+        let nested = [[0u16; 3], [1; 3], [2; 3]];
+        let limits = HashLimits { max_len: 2, ..HashLimits::default() };
+        let mut limited = LimitedHasher::new(CountingHasher::new(), limits);
+        nested.portable_hash(&mut limited);
+        assert_eq!(limited.check(), Err(HashError::LengthTooLarge { len: 3, max_len: 2 }));
+        assert_eq!(limited.get_ref().stats().calls(), 0);
+
+        // Adapters forward the error. A buffered hasher only calls `write`, so only the byte
+        // budget and call limit apply to it.
+        let budget = HashLimits { max_bytes: 16, ..HashLimits::default() };
+        let mut buffered = BufferedHasher::<_, 64>::new(LimitedHasher::new(Sha256Hasher::default(), budget));
+        nested.portable_hash(&mut buffered);
+        assert!(buffered.check().is_err());
+        let mut tee = TeeHasher(Sha256Hasher::default(), LimitedHasher::new(Sha256Hasher::default(), limits));
+        nested.portable_hash(&mut tee);
+        assert!(tee.check().is_err());
+
+        assert_eq!(
+            HashError::LengthTooLarge { len: 3, max_len: 2 }.to_string(),
+            "length 3 exceeds the maximum hashed length of 2",
+        );
+        assert_eq!(HashError::DepthExceeded { max_depth: 4 }.to_string(), "hash input is nested deeper than 4 levels");
+    }
+
+    // ---- Fallible hashing ----
+
+    struct Finite(f64);
+
+    impl portable_hash::TryPortableHash for Finite {
+        type Error = portable_hash::HashError;
+
+        fn try_portable_hash<H: PortableHasher>(&self, state: &mut H) -> Result<(), Self::Error> {
+            if !self.0.is_finite() {
+                return Err(portable_hash::HashError::Invalid("not finite"));
+            }
+            state.write_u64(self.0.to_bits());
+            Ok(())
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum ReadingError {
+        BelowAbsoluteZero,
+        Hash(portable_hash::HashError),
+    }
+
+    impl From<portable_hash::HashError> for ReadingError {
+        fn from(error: portable_hash::HashError) -> Self {
+            ReadingError::Hash(error)
+        }
+    }
+
+    struct Kelvin(u32);
+
+    impl portable_hash::TryPortableHash for Kelvin {
+        type Error = ReadingError;
+
+        fn try_portable_hash<H: PortableHasher>(&self, state: &mut H) -> Result<(), Self::Error> {
+            if self.0 == 0 {
+                return Err(ReadingError::BelowAbsoluteZero);
+            }
+            state.write_u32(self.0);
+            Ok(())
+        }
+    }
+
+    #[derive(PortableHash)]
+    #[portable_hash(fallible)]
+    struct Reading {
+        sensor: u32,
+        value: Finite,
+    }
+
+    #[derive(PortableHash)]
+    #[portable_hash(fallible = "ReadingError", discriminant = "index")]
+    enum Sample<T> {
+        Missing,
+        Value(T, Finite),
+    }
+
+    /// Containers of fallible values don't implement `TryPortableHash`, so they're hashed by hand.
+    struct Series<'a> {
+        points: &'a [Finite],
+    }
+
+    impl portable_hash::TryPortableHash for Series<'_> {
+        type Error = portable_hash::HashError;
+
+        fn try_portable_hash<H: PortableHasher>(&self, state: &mut H) -> Result<(), Self::Error> {
+            portable_hash::encoding::try_hash_slice(self.points, state)
+        }
+    }
+
+    #[test]
+    fn test_fallible_hashing() {
+        use portable_hash::{HashError, HashLimits, LimitedBuildHasher};
+
+        let build = Sha256BuildHasher::default();
+        assert_eq!(
+            build.try_hash_one(&Reading { sensor: 1, value: Finite(0.5) }),
+            Ok(hash_one(&(1u32, 0.5f64.to_bits()))),
+        );
+        assert_eq!(
+            build.try_hash_one(&Reading { sensor: 1, value: Finite(f64::NAN) }),
+            Err(HashError::Invalid("not finite")),
+        );
+
+        // Field errors convert into the container's error type.
+        assert_eq!(
+            build.try_hash_one(&Sample::Value(Kelvin(300), Finite(1.0))),
+            Ok(hash_one(&(1u64, 300u32, 1.0f64.to_bits()))),
+        );
+        assert_eq!(build.try_hash_one(&Sample::<Kelvin>::Missing), Ok(hash_one(&0u64)));
+        assert_eq!(
+            build.try_hash_one(&Sample::Value(Kelvin(0), Finite(1.0))),
+            Err(ReadingError::BelowAbsoluteZero),
+        );
+        assert_eq!(
+            build.try_hash_one(&Sample::Value(Kelvin(300), Finite(f64::INFINITY))),
+            Err(ReadingError::Hash(HashError::Invalid("not finite"))),
+        );
+
+        // Hasher errors are reported too.
+        let limited = LimitedBuildHasher::new(build, HashLimits { max_calls: 2, ..HashLimits::default() });
+        assert_eq!(
+            limited.try_hash_one(&Sample::Value(Kelvin(300), Finite(1.0))),
+            Err(ReadingError::Hash(HashError::CallLimitExceeded { max_calls: 2 })),
+        );
+        // Hasher errors are checked after each field, so later fields aren't hashed.
+        let limited = LimitedBuildHasher::new(Sha256BuildHasher::default(), HashLimits { max_calls: 0, ..HashLimits::default() });
+        assert_eq!(
+            limited.try_hash_one(&Reading { sensor: 1, value: Finite(f64::NAN) }),
+            Err(HashError::CallLimitExceeded { max_calls: 0 }),
+        );
+
+        assert_eq!(Reading::PORTABLE_HASH_SCHEMA, "struct (u32, Finite)");
+    }
+
+    #[test]
+    fn test_try_hash_slice() {
+        use portable_hash::{encoding, HashError, HashLimits, LimitedBuildHasher};
+
+        // The same encoding as a slice of the values' bits.
+        let build = Sha256BuildHasher::default();
+        let points = [Finite(0.5), Finite(2.0)];
+        let bits = [0.5f64.to_bits(), 2.0f64.to_bits()];
+        assert_eq!(build.try_hash_one(&Series { points: &points }), Ok(hash_one(&bits)));
+        assert_eq!(build.try_hash_one(&Series { points: &[] }), Ok(hash_one(&[0u64; 0])));
+        assert_eq!(
+            build.try_hash_one(&Series { points: &[Finite(1.0), Finite(f64::NAN)] }),
+            Err(HashError::Invalid("not finite")),
+        );
+
+        let mut hasher = build.build_hasher();
+        encoding::try_hash_iter(points.iter().rev(), &mut hasher).unwrap();
+        assert_eq!(hasher.finish(), hash_one(&[bits[1], bits[0]]));
+
+        // The hasher is checked after each element.
+        let limited = LimitedBuildHasher::new(build, HashLimits { max_calls: 1, ..HashLimits::default() });
+        assert_eq!(
+            limited.try_hash_one(&Series { points: &[Finite(1.0), Finite(f64::NAN)] }),
+            Err(HashError::CallLimitExceeded { max_calls: 1 }),
+        );
+    }
+
+    // ---- Forwarding impls ----
+
+    /// Takes the hasher by value, as helper functions generic over the hasher often do.
+    fn hash_into<H: PortableHasher, T: PortableHash>(mut state: H, value: &T) -> H {
+        value.portable_hash(&mut state);
+        state
+    }
+
+    fn finalize_with<O, H: PortableHasherOutput<O>>(state: &H) -> O {
+        state.finalize()
+    }
+
+    #[test]
+    fn test_forwarding_hashers() {
+        let mixed = Mixed { name: "forward", data: &[1, 2], id: 3, flags: (-4, 5) };
+        let mut direct = CallLog::default();
+        mixed.portable_hash(&mut direct);
+
+        // Overridden methods are still called through the reference.
+        let mut log = CallLog::default();
+        hash_into(&mut log, &mixed);
+        assert_eq!(log.0, direct.0);
+
+        let mut sha = Sha256Hasher::default();
+        let by_ref = hash_into(&mut sha, &mixed);
+        assert_eq!(by_ref.finish(), hash_one(&mixed));
+        let digest: [u8; 32] = finalize_with(&by_ref);
+        assert_eq!(digest, Sha256BuildHasher::default().finalize_one::<[u8; 32], _>(&mixed));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_boxed_hashers() {
+        use portable_hash::DynPortableHasher;
+
+        let mixed = Mixed { name: "boxed", data: &[1, 2], id: 3, flags: (-4, 5) };
+        let mut direct = CallLog::default();
+        mixed.portable_hash(&mut direct);
+
+        let boxed = hash_into(Box::new(CallLog::default()), &mixed);
+        assert_eq!(boxed.0, direct.0);
+        let erased: Box<dyn DynPortableHasher> = hash_into(Box::new(CallLog::default()), &mixed);
+        assert_eq!(erased.finish(), direct.0.len() as u64);
+
+        let boxed = hash_into(Box::new(Sha256Hasher::default()), &mixed);
+        let short: u128 = finalize_with(&boxed);
+        assert_eq!(short, Sha256BuildHasher::default().finalize_one::<u128, _>(&mixed));
+    }
+
+    // ---- Parallel tree hashing ----
+
+    #[cfg(feature = "rayon")]
+    mod par_tree {
+        use super::*;
+        use portable_hash::par_hash::{par_finalize, par_hash, PAR_HASH_CHUNK_LEN};
+
+        const LENGTHS: [usize; 7] = [
+            0,
+            1,
+            PAR_HASH_CHUNK_LEN - 1,
+            PAR_HASH_CHUNK_LEN,
+            PAR_HASH_CHUNK_LEN + 1,
+            3 * PAR_HASH_CHUNK_LEN,
+            5 * PAR_HASH_CHUNK_LEN + 3,
+        ];
+
+        fn records(len: usize) -> Vec<(u32, &'static str, i64)> {
+            (0..len).map(|i| (i as u32, "record", -(i as i64))).collect()
+        }
+
+        /// The documented tree encoding, hashed on one thread.
+        fn sequential<T: PortableHash>(data: &[T]) -> [u8; 32] {
+            let build = Sha256BuildHasher::default();
+            let mut level: Vec<[u8; 32]> = if data.is_empty() {
+                vec![build.finalize_one((0u8, data))]
+            } else {
+                data.chunks(PAR_HASH_CHUNK_LEN).map(|chunk| build.finalize_one((0u8, chunk))).collect()
+            };
+            while level.len() > 1 {
+                level = level
+                    .chunks(2)
+                    .map(|pair| match pair {
+                        [left, right] => build.finalize_one((1u8, left, right)),
+                        [odd] => *odd,
+                        _ => unreachable!(),
+                    })
+                    .collect();
+            }
+            level[0]
+        }
+
+        #[test]
+        fn test_par_hash() {
+            let build = Sha256BuildHasher::default();
+            for len in LENGTHS {
+                let data = records(len);
+                let digest: [u8; 32] = par_finalize(&build, &data);
+                assert_eq!(digest, sequential(&data), "len {}", len);
+                assert_ne!(par_hash(&build, &data), build.hash_one(&data[..]), "len {}", len);
+            }
+
+            // Every element is hashed, in order.
+            let mut data = records(3 * PAR_HASH_CHUNK_LEN);
+            let before = par_hash(&build, &data);
+            data.swap(0, PAR_HASH_CHUNK_LEN);
+            assert_ne!(par_hash(&build, &data), before);
+        }
+
+        /// Pins the tree encoding, including the chunk length.
+        #[test]
+        fn test_par_hash_fixtures() {
+            let build = Sha256BuildHasher::default();
+            let mut fixtures = portable_hash_tester::FixtureDB::load(Sha256BuildHasher::default(), "tests/par_hash_fixtures.csv");
+            for len in LENGTHS {
+                let data = records(len);
+                let digest: [u8; 32] = par_finalize(&build, &data);
+                fixtures.test_fixture(format!("par_finalize_{}", len), digest);
+                fixtures.test_fixture(format!("par_hash_{}", len), par_hash(&build, &data));
+            }
+            fixtures.finish();
+        }
+    }
+
+    // ---- Compile-time hashing ----
+
+    mod const_keys {
+        use portable_hash::const_hash::{hash_bytes, hash_str, FnvHasher};
+
+        pub const USER: u64 = hash_str("user");
+        pub const MAGIC: u64 = hash_bytes(b"\x7fELF");
+        pub const RECORD: u64 = FnvHasher::new()
+            .str("record")
+            .u8(1)
+            .u16(2)
+            .u32(3)
+            .u64(4)
+            .u128(5)
+            .usize(6)
+            .i8(-1)
+            .i16(-2)
+            .i32(-3)
+            .i64(-4)
+            .i128(-5)
+            .isize(-6)
+            .bytes(&[7, 8])
+            .finish();
+        pub const PENDING: u64 = FnvHasher::new().raw(b"Pending").finish();
+
+        pub fn lookup(key: u64) -> Option<&'static str> {
+            match key {
+                USER => Some("user"),
+                MAGIC => Some("magic"),
+                _ => None,
+            }
+        }
+    }
+
+    #[test]
+    fn test_const_hash() {
+        use portable_hash::const_hash::FnvBuildHasher;
+
+        let build = FnvBuildHasher::default();
+        assert_eq!(const_keys::USER, build.hash_one("user"));
+        assert_eq!(const_keys::MAGIC, build.hash_one(*b"\x7fELF"));
+        assert_eq!(const_keys::MAGIC, build.hash_one(&b"\x7fELF"[..]));
+        assert_eq!(
+            const_keys::RECORD,
+            build.hash_one((
+                ("record", 1u8, 2u16, 3u32, 4u64, 5u128, 6usize),
+                (-1i8, -2i16, -3i32, -4i64, -5i128, -6isize),
+                [7u8, 8],
+            )),
+        );
+        assert_eq!(const_keys::lookup(build.hash_one("user")), Some("user"));
+        assert_eq!(const_keys::lookup(build.hash_one("other")), None);
+
+        // The derive macro hashes variant names with the same FNV-1a function.
+        assert_eq!(const_keys::PENDING, 0x7863_6B92_C1A3_909C);
+        assert_eq!(build.hash_one(NameU64::Pending), build.hash_one(const_keys::PENDING));
+    }
+
+    #[test]
+    fn test_const_hash_fixtures() {
+        portable_hash_tester::test_portable_hasher(
+            portable_hash::const_hash::FnvBuildHasher::default(),
+            "tests/fnv_fixtures.csv",
+        );
+    }
+
+    // ---- Encoding adapters ----
+
+    #[test]
+    fn test_encoding_adapters() {
+        use portable_hash::{BigEndianHasher, CompactLengthsHasher, HashError, U32LengthsHasher};
+
+        fn adapted<H: PortableHasher, T: PortableHash>(mut hasher: H, value: &T) -> u64 {
+            value.portable_hash(&mut hasher);
+            hasher.finish()
+        }
+
+        let value = (0x0102u16, -2i32, 3usize, "ab", &[9u8][..]);
+        assert_eq!(
+            adapted(BigEndianHasher(Sha256Hasher::default()), &value),
+            hash_bytes(&[
+                0x01, 0x02,
+                0xff, 0xff, 0xff, 0xfe,
+                0, 0, 0, 0, 0, 0, 0, 3,
+                0, 0, 0, 0, 0, 0, 0, 2, b'a', b'b',
+                0, 0, 0, 0, 0, 0, 0, 1, 9,
+            ]),
+        );
+        assert_eq!(
+            adapted(CompactLengthsHasher(Sha256Hasher::default()), &value),
+            hash_bytes(&[0x02, 0x01, 0xfe, 0xff, 0xff, 0xff, 3, 0, 0, 0, 0, 0, 0, 0, 2, b'a', b'b', 1, 9]),
+        );
+        assert_eq!(
+            adapted(U32LengthsHasher::new(BigEndianHasher(Sha256Hasher::default())), &value),
+            hash_bytes(&[
+                0x01, 0x02,
+                0xff, 0xff, 0xff, 0xfe,
+                0, 0, 0, 0, 0, 0, 0, 3,
+                0, 0, 0, 2, b'a', b'b',
+                0, 0, 0, 1, 9,
+            ]),
+        );
+
+        // Collections and enum discriminants go through the same methods.
+        let long = [0u8; 300];
+        assert_eq!(
+            adapted(CompactLengthsHasher(Sha256Hasher::default()), &(&[1u16, 1][..], &long[..])),
+            hash_bytes(&[&[2, 1, 0, 1, 0, 0xac, 0x02][..], &long].concat()),
+        );
+        assert_eq!(
+            adapted(BigEndianHasher(Sha256Hasher::default()), &IndexEnum::UnitA),
+            hash_bytes(&4u64.to_be_bytes()),
+        );
+
+        // A length that doesn't fit in a `u32` is reported by `check`, rather than panicking.
+        let mut hasher = U32LengthsHasher::new(Sha256Hasher::default());
+        hasher.write_len_prefix(2);
+        assert_eq!(hasher.check(), Ok(()));
+        if let Ok(len) = usize::try_from(u64::from(u32::MAX) + 1) {
+            hasher.write_len_prefix(len);
+            assert_eq!(hasher.check(), Err(HashError::LengthTooLarge { len, max_len: u32::MAX as usize }));
+        }
+    }
+
+    #[test]
+    fn test_encoding_adapter_fixtures() {
+        use portable_hash::{BigEndianBuildHasher, CompactLengthsBuildHasher, U32LengthsBuildHasher};
+
+        portable_hash_tester::test_portable_hasher(
+            BigEndianBuildHasher(Sha256BuildHasher::default()),
+            "tests/big_endian_fixtures.csv",
+        );
+        portable_hash_tester::test_portable_hasher(
+            CompactLengthsBuildHasher(Sha256BuildHasher::default()),
+            "tests/compact_lengths_fixtures.csv",
+        );
+        portable_hash_tester::test_portable_hasher(
+            U32LengthsBuildHasher(BigEndianBuildHasher(Sha256BuildHasher::default())),
+            "tests/big_endian_u32_lengths_fixtures.csv",
+        );
+    }
+
+    // ---- Field-path tracing ----
+
+    #[cfg(feature = "alloc")]
+    mod trace {
+        use portable_hash::{explain_diff, PortableHash, PortableHasher, TraceHasher};
+
+        #[derive(PortableHash)]
+        struct Item {
+            sku: u32,
+            price: u64,
+        }
+
+        #[derive(PortableHash)]
+        enum Payment {
+            Pending,
+            Paid { amount: u64 },
+        }
+
+        #[derive(PortableHash)]
+        struct Order {
+            items: Vec<Item>,
+            payment: Payment,
+            note: (u8, u8),
+        }
+
+        fn order(price: u64, payment: Payment) -> Order {
+            Order {
+                items: (0..5).map(|sku| Item { sku, price: if sku == 3 { price } else { 10 } }).collect(),
+                payment,
+                note: (1, 2),
+            }
+        }
+
+        #[test]
+        fn test_trace_paths() {
+            let mut hasher = TraceHasher::new();
+            order(10, Payment::Paid { amount: 4 }).portable_hash(&mut hasher);
+            let paths: Vec<&str> = hasher.writes().iter().map(|w| w.path.as_str()).collect();
+            assert_eq!(paths[0], "items");
+            assert_eq!(&paths[1..3], ["items[0].sku", "items[0].price"]);
+            assert_eq!(&paths[11..], ["payment::Paid", "payment::Paid.amount", "note", "note"]);
+            assert_eq!(hasher.finish(), 0);
+        }
+
+        #[test]
+        fn test_trace_through_adapters() {
+            use portable_hash::CountingHasher;
+
+            // Adapters pass `TRACING` through, so slices still report element indexes.
+            let mut hasher = CountingHasher::wrap(TraceHasher::new());
+            order(10, Payment::Pending).portable_hash(&mut hasher);
+            let paths: Vec<String> = hasher.into_inner().into_writes().into_iter().map(|w| w.path).collect();
+            assert_eq!(&paths[1..3], ["items[0].sku", "items[0].price"]);
+        }
+
+        #[test]
+        fn test_trace_through_trait_objects() {
+            use portable_hash::DynPortableHash;
+
+            // Trait objects forward the trace hooks, so paths continue into boxed values.
+            let values = |price| -> Vec<Box<dyn DynPortableHash>> {
+                vec![Box::new(7u8), Box::new(order(price, Payment::Pending))]
+            };
+            let mut hasher = TraceHasher::new();
+            values(10).portable_hash(&mut hasher);
+            let paths: Vec<&str> = hasher.writes().iter().map(|w| w.path.as_str()).collect();
+            assert_eq!(&paths[..5], ["", "[0]", "[1].items", "[1].items[0].sku", "[1].items[0].price"]);
+            assert_eq!(explain_diff(&values(10), &values(12)).unwrap().path(), "[1].items[3].price");
+        }
+
+        #[test]
+        fn test_trace_fallible_error() {
+            use portable_hash::TryPortableHash;
+
+            use super::{Finite, Kelvin, Sample};
+
+            // A failed field still exits its path and its variant's path.
+            let mut hasher = TraceHasher::new();
+            assert!(Sample::Value(Kelvin(0), Finite(1.0)).try_portable_hash(&mut hasher).is_err());
+            1u8.portable_hash(&mut hasher);
+            let paths: Vec<&str> = hasher.writes().iter().map(|w| w.path.as_str()).collect();
+            assert_eq!(paths, ["Value", ""]);
+        }
+
+        #[test]
+        fn test_explain_diff() {
+            let diff = explain_diff(&order(10, Payment::Pending), &order(12, Payment::Pending)).unwrap();
+            assert_eq!(diff.path(), "items[3].price");
+            assert_eq!(diff.index, 8);
+            assert_eq!(
+                diff.to_string(),
+                "hash inputs differ at write 8: left wrote [10, 0, 0, 0, 0, 0, 0, 0] at `items[3].price`, \
+                 right wrote [12, 0, 0, 0, 0, 0, 0, 0] at `items[3].price`",
+            );
+
+            let diff = explain_diff(&order(10, Payment::Pending), &order(10, Payment::Paid { amount: 1 })).unwrap();
+            assert_eq!(diff.right.unwrap().path, "payment::Paid");
+            assert_eq!(diff.left.unwrap().path, "payment::Pending");
+
+            assert!(explain_diff(&order(10, Payment::Pending), &order(10, Payment::Pending)).is_none());
+            // Only the write streams are compared, so differently named types can match.
+            assert!(explain_diff(&Item { sku: 1, price: 2 }, &(1u32, 2u64)).is_none());
+        }
+
+        #[test]
+        fn test_transparent_slice_writes() {
+            // A transparent `u8` newtype keeps the single `write_bytes` call for the whole slice.
+            let bytes = [1u8, 2, 3].map(|value| super::Byte { value, unit: core::marker::PhantomData });
+            let mut hasher = TraceHasher::new();
+            bytes.portable_hash(&mut hasher);
+            let writes: Vec<&[u8]> = hasher.writes().iter().map(|w| w.bytes.as_slice()).collect();
+            assert_eq!(writes, [&[3, 0, 0, 0, 0, 0, 0, 0][..], &[1, 2, 3][..]]);
+        }
+    }
 }